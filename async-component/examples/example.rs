@@ -1,25 +1,21 @@
 use std::time::Duration;
 
-use async_component::{context::ComponentStream, AsyncComponent, StateCell, StreamCell};
+use async_component::{
+    context::ComponentStream, AsyncComponent, IntervalCell, StateCell, StreamCell, StreamEvent,
+};
 use futures::{
     channel::mpsc::{channel, Receiver},
     SinkExt, StreamExt,
 };
-use tokio::time::sleep;
 
 #[tokio::main]
 async fn main() {
     let (mut sender, recv) = channel(8);
 
-    // Spawn task that sends number with 1 sec interval
+    // Spawn task that sends id after 3 secs
     tokio::spawn(async move {
-        let mut i = 0;
-        loop {
-            sleep(Duration::from_secs(1)).await;
-            sender.send(i).await.ok();
-
-            i += 1;
-        }
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        sender.send("user2".to_string()).await.ok();
     });
 
     // Run LoginForm component
@@ -28,7 +24,9 @@ async fn main() {
         password: "1234".to_string().into(),
 
         sub_component: CounterComponent { counter: 0.into() },
-        counter_recv: recv.into(),
+        // Increase counter every 1 sec
+        counter_interval: IntervalCell::new(Duration::from_secs(1)),
+        id_recv: recv.into(),
     })
     .await;
 }
@@ -43,7 +41,7 @@ async fn run<C: AsyncComponent + Drawable>(func: impl FnOnce() -> C) {
     let mut stream = ComponentStream::new(func);
 
     let mut stream = stream.enter();
-    while let Some(_) = stream.next().await {
+    while stream.next().await.is_some() {
         stream.component().draw();
     }
 }
@@ -77,8 +75,11 @@ struct LoginForm {
     #[component]
     sub_component: CounterComponent,
 
-    #[state(Self::on_counter_recv)]
-    counter_recv: StreamCell<Receiver<i32>>,
+    #[state(Self::on_counter_interval)]
+    counter_interval: IntervalCell,
+
+    #[state(Self::on_id_recv)]
+    id_recv: StreamCell<Receiver<String>>,
 }
 
 impl LoginForm {
@@ -97,9 +98,17 @@ impl LoginForm {
         println!("LoginForm updated: {:?}", self);
     }
 
-    // Update sub component when counter number is received through channel
-    fn on_counter_recv(&mut self, counter: i32) {
-        *self.sub_component.counter = counter;
+    // Update sub component counter with number of elapsed ticks
+    fn on_counter_interval(&mut self, ticks: u32) {
+        *self.sub_component.counter += ticks as i32;
+    }
+
+    // Update id when new id is received through channel
+    fn on_id_recv(&mut self, event: StreamEvent<String>) {
+        match event {
+            StreamEvent::Item(id) => *self.id = id,
+            StreamEvent::Ended => println!("Id channel closed"),
+        }
     }
 }
 
@@ -0,0 +1,80 @@
+use async_component::{
+    context::{batch, ComponentStream},
+    AsyncComponent, SharedState, StateCell,
+};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Cells {
+    #[state]
+    a: StateCell<i32>,
+
+    #[state]
+    b: StateCell<i32>,
+
+    #[state]
+    shared: SharedState<i32>,
+}
+
+#[test]
+fn batch_is_delivered_as_single_update() {
+    let shared = SharedState::new(0);
+
+    let mut stream = ComponentStream::new(|| Cells {
+        a: StateCell::new(0),
+        b: StateCell::new(0),
+        shared: shared.clone(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+
+    batch(|| {
+        let mut component = stream.component_mut();
+        *component.a = 1;
+        *component.b = 2;
+
+        shared.set(3);
+    });
+
+    // One signal from the cells and one from the subscription
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.signals(), 2);
+    assert_eq!(
+        report.changed().collect::<Vec<_>>(),
+        [["a"], ["b"], ["shared"]]
+    );
+    assert!(stream.next().now_or_never().is_none());
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn sync_shared_state_signals_after_batch() {
+    use async_component::SyncSharedState;
+
+    #[derive(AsyncComponent)]
+    struct Subscriber {
+        #[state]
+        shared: SyncSharedState<i32>,
+    }
+
+    let shared = SyncSharedState::new(0);
+
+    let mut stream = ComponentStream::new(|| Subscriber {
+        shared: shared.clone(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+
+    batch(|| {
+        shared.set(1);
+        shared.modify(|value| *value += 1);
+
+        assert!(stream.next().now_or_never().is_none());
+    });
+
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.signals(), 1);
+    assert_eq!(*stream.component().shared.read(), 2);
+}
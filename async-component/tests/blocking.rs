@@ -0,0 +1,75 @@
+use std::{panic, sync::mpsc, thread, time::Duration};
+
+use async_component::{
+    blocking::BlockingCell,
+    context::{ComponentStream, EnteredComponentStream},
+    AsyncComponent,
+};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Computed {
+    #[state]
+    value: BlockingCell<i32>,
+}
+
+/// Update stream until `cond` is met
+fn update_until(stream: &mut EnteredComponentStream<Computed>, cond: impl Fn(&Computed) -> bool) {
+    for _ in 0..1000 {
+        stream.next().now_or_never();
+
+        if cond(stream.component()) {
+            return;
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    panic!("Condition is not met in time");
+}
+
+#[test]
+fn older_result_does_not_overwrite_newer() {
+    // First job blocks one worker until second job is finished on another one
+    let mut stream = ComponentStream::new(|| Computed {
+        value: BlockingCell::new(),
+    });
+    let mut stream = stream.enter();
+
+    let (release, wait) = mpsc::channel::<()>();
+    BlockingCell::run(&mut stream.component_mut().value, move || {
+        wait.recv().unwrap();
+        1
+    });
+    BlockingCell::run(&mut stream.component_mut().value, || 2);
+
+    update_until(&mut stream, |component| *component.value == Some(2));
+
+    release.send(()).unwrap();
+    update_until(&mut stream, |component| {
+        !BlockingCell::is_running(&component.value)
+    });
+
+    stream.next().now_or_never();
+    assert_eq!(*stream.component().value, Some(2));
+}
+
+#[test]
+fn job_panic_is_resumed_on_update() {
+    let mut stream = ComponentStream::new(|| Computed {
+        value: BlockingCell::new(),
+    });
+    let mut stream = stream.enter();
+
+    BlockingCell::run(&mut stream.component_mut().value, || panic!("job failed"));
+
+    let panic = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        update_until(&mut stream, |_| false);
+    }))
+    .unwrap_err();
+    assert_eq!(panic.downcast_ref::<&str>(), Some(&"job failed"));
+
+    // Panic is resumed only once
+    BlockingCell::run(&mut stream.component_mut().value, || 1);
+    update_until(&mut stream, |component| *component.value == Some(1));
+}
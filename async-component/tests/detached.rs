@@ -0,0 +1,66 @@
+use std::vec::IntoIter;
+
+use async_component::{
+    context::{try_with_current_context, ComponentStream},
+    AsyncComponent, SharedState, StateCell, StreamCell, StreamEvent,
+};
+use futures::{stream, stream::Iter, FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Detached {
+    #[state]
+    value: StateCell<i32>,
+
+    #[state(Self::on_item)]
+    items: StreamCell<Iter<IntoIter<i32>>>,
+
+    #[state]
+    shared: SharedState<i32>,
+
+    received: Vec<i32>,
+}
+
+impl Detached {
+    fn on_item(&mut self, event: StreamEvent<i32>) {
+        if let StreamEvent::Item(item) = event {
+            self.received.push(item);
+        }
+    }
+}
+
+fn detached() -> Detached {
+    Detached {
+        value: StateCell::new(0),
+        items: StreamCell::new(stream::iter(vec![1])),
+        shared: SharedState::new(0),
+        received: Vec::new(),
+    }
+}
+
+#[test]
+fn component_is_built_and_dropped_outside_context() {
+    assert!(try_with_current_context(|_| ()).is_none());
+
+    let mut component = detached();
+    *component.value = 1;
+    StateCell::set(&mut component.value, 2);
+    component.shared.set(1);
+    drop(component.shared.clone());
+
+    drop(component);
+}
+
+#[test]
+fn detached_component_attaches_on_entry() {
+    let component = detached();
+
+    let mut stream = ComponentStream::new(move || component);
+    let mut stream = stream.enter();
+
+    let report = stream.next().now_or_never().unwrap().unwrap();
+    assert_eq!(
+        report.changed().collect::<Vec<_>>(),
+        [["value"], ["items"], ["shared"]]
+    );
+    assert_eq!(stream.component().received, [1]);
+}
@@ -0,0 +1,52 @@
+use async_component::{context::ComponentStream, AsyncComponent, EventQueue, StateCell};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Emitter {
+    #[state(Self::on_value)]
+    value: StateCell<i32>,
+
+    #[event]
+    events: EventQueue<i32>,
+}
+
+impl Emitter {
+    fn on_value(&mut self, _: ()) {
+        self.events.emit(*self.value);
+    }
+}
+
+#[test]
+fn event_emitted_during_update_does_not_schedule_update() {
+    let mut stream = ComponentStream::new(|| Emitter {
+        value: StateCell::new(0),
+        events: EventQueue::new(),
+    });
+    let mut events = stream.enter().events();
+
+    assert_eq!(events.next().now_or_never().flatten().unwrap(), [0]);
+    assert!(events.next().now_or_never().is_none());
+
+    let mut stream = events.into_inner();
+
+    *stream.component_mut().value = 1;
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.take_events(), [1]);
+    assert!(stream.next().now_or_never().is_none());
+}
+
+#[test]
+fn event_emitted_outside_update_signals() {
+    let mut stream = ComponentStream::new(|| Emitter {
+        value: StateCell::new(0),
+        events: EventQueue::new(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.take_events(), [0]);
+
+    stream.component_mut().events.emit(5);
+    assert!(stream.next().now_or_never().unwrap().unwrap().is_empty());
+    assert_eq!(stream.take_events(), [5]);
+}
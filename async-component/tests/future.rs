@@ -0,0 +1,46 @@
+use async_component::{context::ComponentStream, AsyncComponent, FutureCell};
+use futures::{channel::oneshot, FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Awaited {
+    #[state(Self::on_value)]
+    value: FutureCell<oneshot::Receiver<i32>>,
+
+    received: Vec<i32>,
+}
+
+impl Awaited {
+    fn on_value(&mut self, value: Result<i32, oneshot::Canceled>) {
+        self.received.push(value.unwrap());
+    }
+}
+
+#[test]
+fn reset_drops_pending_future() {
+    let (first, receiver) = oneshot::channel();
+
+    let mut stream = ComponentStream::new(|| Awaited {
+        value: FutureCell::new(receiver),
+        received: Vec::new(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert!(stream.component().value.is_pending());
+
+    let (second, receiver) = oneshot::channel();
+    stream.component_mut().value.reset(receiver);
+    assert!(first.is_canceled());
+
+    second.send(2).unwrap();
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().received, [2]);
+    assert!(stream.component().value.is_completed());
+
+    // Completed cell yields again after reset
+    let (third, receiver) = oneshot::channel();
+    stream.component_mut().value.reset(receiver);
+    third.send(3).unwrap();
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().received, [2, 3]);
+}
@@ -0,0 +1,25 @@
+use async_component::HistoryCell;
+
+#[test]
+fn unchanged_transaction_keeps_history() {
+    let mut cell = HistoryCell::new(1);
+
+    HistoryCell::set(&mut cell, 2);
+    assert!(HistoryCell::undo(&mut cell));
+    assert!(HistoryCell::can_redo(&cell));
+
+    HistoryCell::transaction(&mut cell, |value| *value = 1);
+    assert!(HistoryCell::can_redo(&cell));
+    assert!(!HistoryCell::can_undo(&cell));
+
+    HistoryCell::transaction(&mut cell, |value| {
+        *value += 1;
+        *value += 1;
+    });
+    assert_eq!(*cell, 3);
+    assert!(!HistoryCell::can_redo(&cell));
+
+    assert!(HistoryCell::undo(&mut cell));
+    assert_eq!(*cell, 1);
+    assert!(!HistoryCell::can_undo(&cell));
+}
@@ -0,0 +1,54 @@
+use async_component::{context::ComponentStream, AsyncComponent, MemoCell, StateCell};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Sum {
+    #[state]
+    a: StateCell<i32>,
+
+    #[state]
+    b: StateCell<i32>,
+
+    #[state]
+    unrelated: StateCell<i32>,
+
+    #[memo(Self::compute)]
+    #[state]
+    positive: MemoCell<bool>,
+}
+
+impl Sum {
+    fn compute(&self) -> bool {
+        *self.a + *self.b > 0
+    }
+}
+
+#[test]
+fn memo_recomputes_only_on_dependency_change() {
+    let mut stream = ComponentStream::new(|| Sum {
+        a: 1.into(),
+        b: 1.into(),
+        unrelated: 0.into(),
+        positive: MemoCell::new(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().positive.get(), Some(&true));
+
+    *stream.component_mut().unrelated = 3;
+    assert!(!stream.component().positive.is_stale());
+
+    *stream.component_mut().a = -5;
+    assert!(stream.component().positive.is_stale());
+
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(
+        report.changed().collect::<Vec<_>>(),
+        [["a"], ["unrelated"], ["positive"]]
+    );
+    assert_eq!(stream.component().positive.get(), Some(&false));
+
+    // Memo change is reported in the same update without scheduling another one
+    assert!(stream.next().now_or_never().is_none());
+}
@@ -0,0 +1,90 @@
+use async_component::{context::ComponentStream, AsyncComponent, FutureCell, StateCell};
+use futures::{future::BoxFuture, FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Counter {
+    #[state]
+    value: StateCell<i32>,
+}
+
+fn counter() -> ComponentStream<Counter> {
+    ComponentStream::new(|| Counter {
+        value: StateCell::new(0),
+    })
+}
+
+#[test]
+fn component_mut_signals_own_stream() {
+    let mut s1 = counter();
+    let mut s2 = counter();
+
+    let mut e1 = s1.enter();
+    let mut e2 = s2.enter();
+
+    assert!(e1.next().now_or_never().is_some());
+    assert!(e2.next().now_or_never().is_some());
+
+    *e1.component_mut().value = 5;
+
+    assert!(e2.next().now_or_never().is_none());
+    let report = e1.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.changed().collect::<Vec<_>>(), [["value"]]);
+    assert!(report.is_changed(&["value"]));
+    assert!(!report.is_changed(&["value", "inner"]));
+}
+
+#[test]
+fn streams_exited_out_of_order() {
+    let mut s1 = counter();
+    let mut s2 = counter();
+
+    let mut e1 = s1.enter();
+    let mut e2 = s2.enter();
+
+    assert!(e1.next().now_or_never().is_some());
+    assert!(e2.next().now_or_never().is_some());
+
+    drop(e1);
+    *e2.component_mut().value = 1;
+
+    let report = e2.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.changed().collect::<Vec<_>>(), [["value"]]);
+    drop(e2);
+
+    let mut e1 = s1.enter();
+    assert!(e1.next().now_or_never().is_none());
+}
+
+#[derive(AsyncComponent)]
+struct Spawner {
+    #[state(Self::on_inner)]
+    inner: FutureCell<BoxFuture<'static, i32>>,
+
+    value: Option<i32>,
+}
+
+impl Spawner {
+    fn on_inner(&mut self, value: i32) {
+        self.value = Some(value);
+    }
+}
+
+#[test]
+fn stream_created_inside_polled_future() {
+    let mut stream = ComponentStream::new(|| Spawner {
+        inner: FutureCell::new(
+            async {
+                let mut inner = counter();
+                inner.enter().next().await;
+
+                *inner.component().value
+            }
+            .boxed(),
+        ),
+        value: None,
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().value, Some(0));
+}
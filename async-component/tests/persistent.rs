@@ -0,0 +1,125 @@
+#![cfg(feature = "serde")]
+
+use std::{fs, path::PathBuf, sync::Arc, thread, time::Duration};
+
+use async_component::{
+    context::{ComponentStream, EnteredComponentStream},
+    persistent::{PersistError, PersistentCell},
+    timer::ManualTimer,
+    AsyncComponent,
+};
+use futures::{FutureExt, StreamExt};
+
+const DELAY: Duration = Duration::from_millis(100);
+
+#[derive(AsyncComponent)]
+struct Settings {
+    #[state]
+    value: PersistentCell<i32>,
+}
+
+/// Empty directory unique to the test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "async-component-persistent-{}-{name}",
+        std::process::id()
+    ));
+
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+fn settings(timer: &Arc<ManualTimer>, path: PathBuf) -> ComponentStream<Settings> {
+    ComponentStream::with_timer(timer.clone(), || Settings {
+        value: PersistentCell::new(path, DELAY, || 0),
+    })
+}
+
+/// Update stream until `cond` is met, waiting for blocking worker
+fn update_until(stream: &mut EnteredComponentStream<Settings>, cond: impl Fn(&Settings) -> bool) {
+    for _ in 0..1000 {
+        stream.next().now_or_never();
+
+        if cond(stream.component()) {
+            return;
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    panic!("Condition is not met in time");
+}
+
+#[test]
+fn loads_and_saves_after_delay() {
+    let dir = temp_dir("round-trip");
+    let path = dir.join("value.json");
+    fs::write(&path, "5").unwrap();
+
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = settings(&timer, path.clone());
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(*stream.component().value, 5);
+    assert!(PersistentCell::error(&stream.component().value).is_none());
+
+    *stream.component_mut().value = 6;
+    assert!(stream.next().now_or_never().is_some());
+
+    // Change postpones save
+    timer.advance(DELAY / 2);
+    *stream.component_mut().value = 7;
+    assert!(stream.next().now_or_never().is_some());
+
+    timer.advance(DELAY * 3 / 4);
+    assert!(stream.next().now_or_never().is_some());
+    assert!(PersistentCell::is_dirty(&stream.component().value));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "5");
+
+    timer.advance(DELAY / 4);
+    update_until(&mut stream, |component| {
+        !PersistentCell::is_dirty(&component.value)
+    });
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "7");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn errors_are_kept_as_state() {
+    let dir = temp_dir("errors");
+    let path = dir.join("value.json");
+    fs::write(&path, "not json").unwrap();
+
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = settings(&timer, path);
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(*stream.component().value, 0);
+    assert!(matches!(
+        PersistentCell::error(&stream.component().value),
+        Some(PersistError::Format(_))
+    ));
+
+    // Parent directory is removed, so save fails
+    fs::remove_dir_all(&dir).unwrap();
+
+    *stream.component_mut().value = 1;
+    assert!(stream.next().now_or_never().is_some());
+
+    timer.advance(DELAY);
+    update_until(&mut stream, |component| {
+        !PersistentCell::is_dirty(&component.value)
+    });
+
+    assert!(matches!(
+        PersistentCell::error(&stream.component().value),
+        Some(PersistError::Io(_))
+    ));
+}
@@ -0,0 +1,113 @@
+use std::{future::Future, pin::Pin};
+
+use async_component::{
+    components::boxed::BoxedComponent, context::ComponentStream, AsyncComponent, FutureCell,
+    StreamCell, StreamEvent,
+};
+use futures::{stream, FutureExt, Stream, StreamExt};
+
+async fn value_later(value: i32) -> i32 {
+    value
+}
+
+#[derive(AsyncComponent)]
+struct PinnedComponent<S: Stream<Item = i32>, F: Future<Output = i32>> {
+    #[pin]
+    #[state(Self::on_item)]
+    items: StreamCell<S>,
+
+    #[pin]
+    #[state(Self::on_value)]
+    value: FutureCell<F>,
+
+    received: Vec<i32>,
+}
+
+impl<S: Stream<Item = i32>, F: Future<Output = i32>> PinnedComponent<S, F> {
+    fn on_item(self: Pin<&mut Self>, event: StreamEvent<i32>) {
+        if let StreamEvent::Item(item) = event {
+            self.project().received.push(item);
+        }
+    }
+
+    fn on_value(self: Pin<&mut Self>, value: i32) {
+        self.project().received.push(value);
+    }
+}
+
+#[test]
+fn pinned_fields_hold_unboxed_futures() {
+    let mut stream = ComponentStream::new(|| PinnedComponent {
+        items: StreamCell::new(stream::once(value_later(1))),
+        value: FutureCell::new(value_later(2)),
+        received: Vec::new(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().received, [1, 2]);
+    assert!(stream.component().value.is_completed());
+
+    stream
+        .component_mut()
+        .as_pin_mut()
+        .project()
+        .value
+        .reset_pinned(value_later(3));
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().received, [1, 2, 3]);
+}
+
+#[derive(AsyncComponent)]
+struct Parent<S: Stream<Item = i32>, F: Future<Output = i32>> {
+    #[pin]
+    #[component]
+    child: PinnedComponent<S, F>,
+}
+
+#[test]
+fn pinned_child_component() {
+    let mut stream = ComponentStream::new(|| Parent {
+        child: PinnedComponent {
+            items: StreamCell::new(stream::once(value_later(1))),
+            value: FutureCell::new(value_later(2)),
+            received: Vec::new(),
+        },
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().child.received, [1, 2]);
+}
+
+#[derive(AsyncComponent)]
+#[component(Self::on_update)]
+struct Unpinned<C: AsyncComponent> {
+    #[component]
+    child: C,
+
+    updates: usize,
+}
+
+impl<C: AsyncComponent> Unpinned<C> {
+    fn on_update(&mut self) {
+        self.updates += 1;
+    }
+}
+
+#[test]
+fn component_without_pinned_fields_takes_mut_self() {
+    let mut stream = ComponentStream::new(|| Unpinned {
+        child: BoxedComponent::<dyn AsyncComponent>::from(Box::new(PinnedComponent {
+            items: StreamCell::new(stream::once(value_later(1))),
+            value: FutureCell::new(value_later(2)),
+            received: Vec::new(),
+        }) as Box<dyn AsyncComponent>),
+        updates: 0,
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().updates, 1);
+}
@@ -0,0 +1,97 @@
+#![cfg(all(unix, feature = "process"))]
+
+use std::{
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use async_component::{
+    context::{ComponentStream, EnteredComponentStream},
+    process::{ProcessCell, ProcessEvent},
+    AsyncComponent,
+};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Process {
+    #[state(Self::on_event)]
+    process: ProcessCell,
+
+    events: Vec<ProcessEvent>,
+}
+
+impl Process {
+    fn on_event(&mut self, event: ProcessEvent) {
+        self.events.push(event);
+    }
+}
+
+fn spawn(script: &str) -> ComponentStream<Process> {
+    ComponentStream::new(|| Process {
+        process: ProcessCell::spawn(Command::new("sh").args(["-c", script])).unwrap(),
+        events: Vec::new(),
+    })
+}
+
+/// Update stream until `cond` is met
+fn update_until(stream: &mut EnteredComponentStream<Process>, cond: impl Fn(&Process) -> bool) {
+    for _ in 0..1000 {
+        stream.next().now_or_never();
+
+        if cond(stream.component()) {
+            return;
+        }
+
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    panic!("Condition is not met in time");
+}
+
+#[test]
+fn reports_output_lines_and_exit() {
+    let mut stream = spawn("echo a; echo b >&2; exit 3");
+    let mut stream = stream.enter();
+
+    update_until(&mut stream, |component| component.process.is_exited());
+
+    let events = &stream.component().events;
+    assert_eq!(events.len(), 3);
+    assert!(events.contains(&ProcessEvent::Stdout("a".into())));
+    assert!(events.contains(&ProcessEvent::Stderr("b".into())));
+    assert!(matches!(
+        events.last(),
+        Some(ProcessEvent::Exited(status)) if status.code() == Some(3)
+    ));
+}
+
+#[test]
+fn process_killed_on_drop() {
+    let mut stream = spawn("echo started; exec sleep 10");
+    let id = stream.component().process.id();
+
+    update_until(&mut stream.enter(), |component| {
+        !component.events.is_empty()
+    });
+    drop(stream);
+
+    let alive = || {
+        Command::new("kill")
+            .args(["-0", &id.to_string()])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap()
+            .success()
+    };
+
+    for _ in 0..1000 {
+        if !alive() {
+            return;
+        }
+
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    panic!("Process is not killed");
+}
@@ -0,0 +1,112 @@
+use std::{cell::RefCell, rc::Rc};
+
+use async_component::{
+    context::ComponentStream,
+    query::{QueryCell, QueryClient},
+    AsyncComponent,
+};
+use futures::{channel::oneshot, FutureExt, StreamExt};
+
+type Client = QueryClient<u32, i32, ()>;
+
+/// Loader whose fetches are completed manually
+#[derive(Default)]
+struct Loader {
+    pending: RefCell<Vec<oneshot::Sender<i32>>>,
+    calls: RefCell<usize>,
+}
+
+impl Loader {
+    fn client(self: &Rc<Self>) -> Client {
+        let loader = self.clone();
+
+        QueryClient::new(move |_| {
+            let (sender, receiver) = oneshot::channel();
+
+            loader.pending.borrow_mut().push(sender);
+            *loader.calls.borrow_mut() += 1;
+
+            async move { receiver.await.map_err(|_| ()) }
+        })
+    }
+
+    fn calls(&self) -> usize {
+        *self.calls.borrow()
+    }
+
+    fn complete(&self, value: i32) {
+        for sender in self.pending.borrow_mut().drain(..) {
+            sender.send(value).unwrap();
+        }
+    }
+}
+
+#[derive(AsyncComponent)]
+struct Query {
+    #[state]
+    query: QueryCell<u32, i32, ()>,
+}
+
+fn query(client: &Client) -> ComponentStream<Query> {
+    ComponentStream::new(|| Query {
+        query: QueryCell::new(client, 1),
+    })
+}
+
+#[test]
+fn subscribers_share_fetch_and_keep_stale_data() {
+    let loader = Rc::new(Loader::default());
+    let client = loader.client();
+
+    let mut s1 = query(&client);
+    let mut s2 = query(&client);
+    let mut e1 = s1.enter();
+    let mut e2 = s2.enter();
+
+    assert!(e1.next().now_or_never().is_some());
+    assert!(e2.next().now_or_never().is_some());
+    assert_eq!(loader.calls(), 1);
+    assert!(e2.component().query.is_fetching());
+
+    loader.complete(10);
+
+    // Both subscribers are woken by completed fetch
+    assert!(e1.next().now_or_never().is_some());
+    assert!(e2.next().now_or_never().is_some());
+    assert_eq!(e1.component().query.data().as_deref(), Some(&10));
+    assert_eq!(e2.component().query.data().as_deref(), Some(&10));
+
+    client.invalidate(&1);
+    assert!(e1.next().now_or_never().is_some());
+    assert!(e2.next().now_or_never().is_some());
+    assert_eq!(loader.calls(), 2);
+
+    // Stale data is kept while revalidating
+    assert!(e1.component().query.is_fetching());
+    assert_eq!(e1.component().query.data().as_deref(), Some(&10));
+
+    loader.complete(20);
+
+    assert!(e1.next().now_or_never().is_some());
+    assert!(e2.next().now_or_never().is_some());
+    assert_eq!(e1.component().query.data().as_deref(), Some(&20));
+    assert_eq!(e2.component().query.data().as_deref(), Some(&20));
+    assert_eq!(loader.calls(), 2);
+}
+
+#[test]
+fn fetch_dropped_with_last_subscriber() {
+    let loader = Rc::new(Loader::default());
+    let client = loader.client();
+
+    let mut stream = query(&client);
+    assert!(stream.enter().next().now_or_never().is_some());
+    assert_eq!(loader.calls(), 1);
+
+    drop(stream);
+    assert!(loader.pending.borrow()[0].is_canceled());
+
+    let mut stream = query(&client);
+    assert!(stream.enter().next().now_or_never().is_some());
+    assert_eq!(loader.calls(), 2);
+}
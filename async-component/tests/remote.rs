@@ -0,0 +1,42 @@
+use std::{thread, time::Duration};
+
+use async_component::{context::ComponentStream, AsyncComponent, RemoteState};
+use futures::{executor::block_on, FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Remote {
+    #[state]
+    value: RemoteState<i32>,
+}
+
+#[test]
+fn handle_sets_value_from_other_thread() {
+    let mut stream = ComponentStream::new(|| Remote {
+        value: RemoteState::new(0),
+    });
+    let handle = {
+        let mut stream = stream.enter();
+        assert!(stream.next().now_or_never().is_some());
+        assert!(stream.next().now_or_never().is_none());
+
+        let handle = RemoteState::handle(&stream.component().value);
+        let remote = handle.clone();
+        let setter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            assert!(remote.set(5));
+        });
+
+        // Woken by the other thread
+        let report = block_on(stream.next()).unwrap();
+        assert_eq!(report.changed().collect::<Vec<_>>(), [["value"]]);
+        assert_eq!(*stream.component().value, 5);
+
+        setter.join().unwrap();
+        handle
+    };
+
+    assert!(!handle.is_closed());
+    drop(stream);
+    assert!(handle.is_closed());
+    assert!(!handle.set(1));
+}
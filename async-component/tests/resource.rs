@@ -0,0 +1,62 @@
+use std::{cell::RefCell, rc::Rc};
+
+use async_component::{context::ComponentStream, resource::Resource, AsyncComponent, ResourceCell};
+use futures::{channel::oneshot, FutureExt, StreamExt};
+
+type Pending = Rc<RefCell<Vec<(u32, oneshot::Sender<i32>)>>>;
+
+#[derive(AsyncComponent)]
+struct Loaded {
+    #[state]
+    value: ResourceCell<u32, i32, ()>,
+}
+
+/// Resource whose fetches are completed manually
+fn loaded(pending: &Pending) -> ComponentStream<Loaded> {
+    let pending = pending.clone();
+
+    ComponentStream::new(move || Loaded {
+        value: ResourceCell::new(1, move |key| {
+            let (sender, receiver) = oneshot::channel();
+            pending.borrow_mut().push((*key, sender));
+
+            async move { receiver.await.map_err(|_| ()) }
+        }),
+    })
+}
+
+#[test]
+fn key_change_cancels_in_flight_fetch() {
+    let pending = Pending::default();
+    let mut stream = loaded(&pending);
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert!(stream.component().value.is_loading());
+
+    ResourceCell::set_key(&mut stream.component_mut().value, 2);
+    assert!(stream.next().now_or_never().is_some());
+
+    let (first, second) = {
+        let mut pending = pending.borrow_mut();
+        let second = pending.pop().unwrap();
+        (pending.pop().unwrap(), second)
+    };
+    assert_eq!(first.0, 1);
+    assert!(first.1.is_canceled());
+    assert_eq!(second.0, 2);
+
+    second.1.send(20).unwrap();
+    assert_eq!(
+        stream
+            .next()
+            .now_or_never()
+            .unwrap()
+            .unwrap()
+            .changed()
+            .collect::<Vec<_>>(),
+        [["value"]]
+    );
+    assert_eq!(*stream.component().value, Resource::Ready(20));
+    assert!(!ResourceCell::is_fetching(&stream.component().value));
+}
@@ -0,0 +1,137 @@
+use async_component::{
+    components::option::OptionComponent, context::ComponentStream, AsyncComponent, FutureCell,
+    StateCell,
+};
+use futures::{
+    channel::oneshot::{self, Canceled, Receiver, Sender},
+    FutureExt, StreamExt,
+};
+
+#[derive(AsyncComponent)]
+#[component(Self::on_update)]
+struct Child {
+    #[state]
+    doubled: StateCell<i32>,
+
+    #[state(Self::on_value)]
+    value: FutureCell<Receiver<i32>>,
+
+    updates: usize,
+}
+
+impl Child {
+    fn new() -> (Sender<i32>, Self) {
+        let (sender, receiver) = oneshot::channel();
+
+        (
+            sender,
+            Self {
+                doubled: StateCell::new(0),
+                value: FutureCell::new(receiver),
+                updates: 0,
+            },
+        )
+    }
+
+    fn on_value(&mut self, value: Result<i32, Canceled>) {
+        *self.doubled = value.unwrap() * 2;
+    }
+
+    fn on_update(&mut self) {
+        self.updates += 1;
+    }
+}
+
+#[derive(AsyncComponent)]
+struct Parent {
+    #[component]
+    a: Child,
+
+    #[component]
+    b: Child,
+}
+
+#[test]
+fn only_signaled_child_is_updated() {
+    let (sender, a) = Child::new();
+    let (_b, b) = Child::new();
+
+    let mut stream = ComponentStream::new(|| Parent { a, b });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().a.updates, 1);
+    assert_eq!(stream.component().b.updates, 1);
+
+    // Future of `a` wakes scope of `a` only
+    sender.send(1).unwrap();
+
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.changed().collect::<Vec<_>>(), [["a", "value"]]);
+    assert_eq!(stream.component().a.updates, 2);
+    assert_eq!(stream.component().b.updates, 1);
+
+    // Signal sent while updating `a` updates `a` only
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.changed().collect::<Vec<_>>(), [["a", "doubled"]]);
+    assert_eq!(*stream.component().a.doubled, 2);
+    assert_eq!(stream.component().a.updates, 3);
+    assert_eq!(stream.component().b.updates, 1);
+
+    assert!(stream.next().now_or_never().is_none());
+}
+
+#[test]
+fn signal_outside_update_updates_every_child() {
+    let (_a, a) = Child::new();
+    let (_b, b) = Child::new();
+
+    let mut stream = ComponentStream::new(|| Parent { a, b });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+
+    *stream.component_mut().b.doubled = 5;
+
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.changed().collect::<Vec<_>>(), [["b", "doubled"]]);
+    assert_eq!(stream.component().a.updates, 2);
+    assert_eq!(stream.component().b.updates, 2);
+}
+
+#[derive(AsyncComponent)]
+struct Lazy {
+    #[state(Self::on_show)]
+    show: FutureCell<Receiver<()>>,
+
+    #[component]
+    child: OptionComponent<Child>,
+
+    spare: Option<Child>,
+}
+
+impl Lazy {
+    fn on_show(&mut self, _: Result<(), Canceled>) {
+        *self.child = self.spare.take();
+    }
+}
+
+#[test]
+fn child_inserted_during_update_is_updated() {
+    let (sender, receiver) = oneshot::channel();
+    let (_spare, spare) = Child::new();
+
+    let mut stream = ComponentStream::new(|| Lazy {
+        show: FutureCell::new(receiver),
+        child: OptionComponent(None),
+        spare: Some(spare),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+
+    sender.send(()).unwrap();
+    assert!(stream.next().now_or_never().is_some());
+    assert!(stream.next().now_or_never().is_some());
+    assert_eq!(stream.component().child.as_ref().unwrap().updates, 1);
+}
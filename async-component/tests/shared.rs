@@ -0,0 +1,49 @@
+use async_component::{
+    context::{ComponentStream, EnteredComponentStream},
+    AsyncComponent, SharedState,
+};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Subscriber {
+    #[state]
+    shared: SharedState<i32>,
+}
+
+/// Fields changed by pending update, or [`None`] if stream was not signaled
+fn poll_changed(stream: &mut EnteredComponentStream<Subscriber>) -> Option<Vec<String>> {
+    stream
+        .next()
+        .now_or_never()
+        .flatten()
+        .map(|report| report.changed().map(|path| path.join(".")).collect())
+}
+
+#[test]
+fn every_subscriber_sees_change_once() {
+    let shared = SharedState::new(0);
+
+    let mut s1 = ComponentStream::new(|| Subscriber {
+        shared: shared.clone(),
+    });
+    let mut s2 = ComponentStream::new(|| Subscriber {
+        shared: shared.clone(),
+    });
+    let mut e1 = s1.enter();
+    let mut e2 = s2.enter();
+
+    assert_eq!(poll_changed(&mut e1).unwrap(), ["shared"]);
+    assert_eq!(poll_changed(&mut e2).unwrap(), ["shared"]);
+    assert!(poll_changed(&mut e1).is_none());
+    assert!(poll_changed(&mut e2).is_none());
+
+    shared.set(1);
+
+    assert_eq!(poll_changed(&mut e1).unwrap(), ["shared"]);
+    assert_eq!(*e1.component().shared.borrow(), 1);
+    assert!(poll_changed(&mut e1).is_none());
+
+    assert_eq!(poll_changed(&mut e2).unwrap(), ["shared"]);
+    assert_eq!(*e2.component().shared.borrow(), 1);
+    assert!(poll_changed(&mut e2).is_none());
+}
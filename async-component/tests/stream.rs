@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+use async_component::{
+    context::ComponentStream, AsyncComponent, BatchStreamCell, StreamCell, StreamEvent,
+};
+use futures::{channel::mpsc, stream, stream::Iter, FutureExt, StreamExt};
+
+type Items = Iter<Range<i32>>;
+
+#[derive(AsyncComponent)]
+struct Batched {
+    #[state(Self::on_batch)]
+    items: BatchStreamCell<Items>,
+
+    events: Vec<StreamEvent<Vec<i32>>>,
+}
+
+impl Batched {
+    fn on_batch(&mut self, event: StreamEvent<Vec<i32>>) {
+        self.events.push(event);
+    }
+}
+
+#[test]
+fn batch_is_split_by_max_batch() {
+    let mut stream = ComponentStream::new(|| Batched {
+        items: BatchStreamCell::with_max_batch(stream::iter(0..5), 2),
+        events: Vec::new(),
+    });
+    let mut stream = stream.enter();
+
+    // Remaining items are drained on following updates
+    for _ in 0..4 {
+        assert!(stream.next().now_or_never().is_some());
+    }
+    assert!(stream.next().now_or_never().is_none());
+
+    assert_eq!(
+        stream.component().events,
+        [
+            StreamEvent::Item(vec![0, 1]),
+            StreamEvent::Item(vec![2, 3]),
+            StreamEvent::Item(vec![4]),
+            StreamEvent::Ended,
+        ]
+    );
+    assert!(stream.component().items.is_terminated());
+}
+
+#[derive(AsyncComponent)]
+struct Streamed {
+    #[state(Self::on_event)]
+    items: StreamCell<mpsc::UnboundedReceiver<i32>>,
+
+    events: Vec<StreamEvent<i32>>,
+}
+
+impl Streamed {
+    fn on_event(&mut self, event: StreamEvent<i32>) {
+        self.events.push(event);
+    }
+}
+
+#[test]
+fn end_is_reported_once() {
+    let (sender, receiver) = mpsc::unbounded();
+
+    let mut stream = ComponentStream::new(|| Streamed {
+        items: StreamCell::new(receiver),
+        events: Vec::new(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+
+    sender.unbounded_send(1).unwrap();
+    assert!(stream.next().now_or_never().is_some());
+
+    drop(sender);
+    assert!(stream.next().now_or_never().is_some());
+    assert!(stream.component().items.is_terminated());
+
+    // Terminated stream is not polled again
+    assert!(stream.next().now_or_never().is_none());
+
+    assert_eq!(
+        stream.component().events,
+        [StreamEvent::Item(1), StreamEvent::Ended]
+    );
+}
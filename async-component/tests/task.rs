@@ -0,0 +1,51 @@
+use async_component::{context::ComponentStream, AsyncComponent, TaskCell};
+use futures::{channel::oneshot, FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Tasks {
+    #[state(Self::on_done)]
+    tasks: TaskCell<i32>,
+
+    done: Vec<i32>,
+}
+
+impl Tasks {
+    fn on_done(&mut self, value: i32) {
+        self.done.push(value);
+    }
+}
+
+#[test]
+fn drop_cancels_running_tasks() {
+    let (first, first_wait) = oneshot::channel::<i32>();
+    let (second, second_wait) = oneshot::channel::<i32>();
+
+    let mut stream = ComponentStream::new(|| Tasks {
+        tasks: TaskCell::new(),
+        done: Vec::new(),
+    });
+    {
+        let mut stream = stream.enter();
+
+        let mut component = stream.component_mut();
+        component
+            .tasks
+            .spawn(async move { first_wait.await.unwrap_or(-1) });
+        component
+            .tasks
+            .spawn(async move { second_wait.await.unwrap_or(-1) });
+        drop(component);
+
+        assert!(stream.next().now_or_never().is_some());
+        assert_eq!(stream.component().tasks.len(), 2);
+
+        first.send(1).unwrap();
+        assert!(stream.next().now_or_never().is_some());
+        assert_eq!(stream.component().done, [1]);
+        assert_eq!(stream.component().tasks.len(), 1);
+    }
+
+    assert!(!second.is_canceled());
+    drop(stream);
+    assert!(second.is_canceled());
+}
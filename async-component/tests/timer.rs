@@ -0,0 +1,231 @@
+use std::{sync::Arc, time::Duration};
+
+use async_component::{
+    context::{ComponentStream, EnteredComponentStream},
+    timer::{ManualTimer, Timer},
+    AsyncComponent, DeadlineCell, DebounceCell, IntervalCell, ThrottleCell, TimeoutCell,
+};
+use futures::{FutureExt, StreamExt};
+
+const MS: Duration = Duration::from_millis(1);
+
+const NOTHING: [&str; 0] = [];
+
+/// Fields changed by pending update, or [`None`] if stream was not signaled
+fn poll_changed<C: AsyncComponent>(stream: &mut EnteredComponentStream<C>) -> Option<Vec<String>> {
+    stream
+        .next()
+        .now_or_never()
+        .flatten()
+        .map(|report| report.changed().map(|path| path.join(".")).collect())
+}
+
+#[derive(AsyncComponent)]
+struct Debounced {
+    #[state]
+    value: DebounceCell<i32>,
+}
+
+#[test]
+fn debounce_reports_after_quiet_period() {
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = ComponentStream::with_timer(timer.clone(), || Debounced {
+        value: DebounceCell::new(0, 100 * MS),
+    });
+    let mut stream = stream.enter();
+
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["value"]);
+
+    *stream.component_mut().value = 1;
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    timer.advance(50 * MS);
+    *stream.component_mut().value = 2;
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    // First deadline passed but value was changed again since
+    timer.advance(60 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+    assert!(DebounceCell::is_pending(&stream.component().value));
+
+    timer.advance(40 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["value"]);
+    assert_eq!(*stream.component().value, 2);
+
+    assert!(poll_changed(&mut stream).is_none());
+}
+
+#[derive(AsyncComponent)]
+struct Throttled {
+    #[state]
+    value: ThrottleCell<i32>,
+}
+
+#[test]
+fn throttle_reports_at_most_once_per_interval() {
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = ComponentStream::with_timer(timer.clone(), || Throttled {
+        value: ThrottleCell::new(0, 100 * MS),
+    });
+    let mut stream = stream.enter();
+
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["value"]);
+
+    *stream.component_mut().value = 1;
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    timer.advance(30 * MS);
+    *stream.component_mut().value = 2;
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    timer.advance(70 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["value"]);
+    assert_eq!(*stream.component().value, 2);
+    assert!(!ThrottleCell::is_pending(&stream.component().value));
+
+    assert!(poll_changed(&mut stream).is_none());
+}
+
+#[derive(AsyncComponent)]
+struct Ticker {
+    #[state(Self::on_tick)]
+    interval: IntervalCell,
+
+    ticks: u32,
+}
+
+impl Ticker {
+    fn on_tick(&mut self, ticks: u32) {
+        self.ticks += ticks;
+    }
+}
+
+#[test]
+fn interval_fires_every_period_and_coalesces_missed_ticks() {
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = ComponentStream::with_timer(timer.clone(), || Ticker {
+        interval: IntervalCell::new(100 * MS),
+        ticks: 0,
+    });
+    let mut stream = stream.enter();
+
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+    assert!(poll_changed(&mut stream).is_none());
+
+    timer.advance(100 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["interval"]);
+    assert_eq!(stream.component().ticks, 1);
+
+    timer.advance(350 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["interval"]);
+    assert_eq!(stream.component().ticks, 4);
+
+    timer.advance(40 * MS);
+    assert!(poll_changed(&mut stream).is_none());
+
+    timer.advance(10 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["interval"]);
+    assert_eq!(stream.component().ticks, 5);
+}
+
+#[derive(AsyncComponent)]
+struct Deadline {
+    #[state]
+    deadline: DeadlineCell,
+}
+
+#[test]
+fn deadline_fires_once_when_reached() {
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = ComponentStream::with_timer(timer.clone(), || Deadline {
+        deadline: DeadlineCell::new(timer.now() + 100 * MS),
+    });
+    let mut stream = stream.enter();
+
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    timer.advance(99 * MS);
+    assert!(poll_changed(&mut stream).is_none());
+
+    timer.advance(MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["deadline"]);
+    assert!(stream.component().deadline.is_elapsed());
+
+    timer.advance(100 * MS);
+    assert!(poll_changed(&mut stream).is_none());
+
+    // New deadline fires again
+    let deadline = timer.now() + 50 * MS;
+    stream.component_mut().deadline.set(deadline);
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    timer.advance(50 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["deadline"]);
+}
+
+#[derive(AsyncComponent)]
+struct Timeout {
+    #[state]
+    timeout: TimeoutCell,
+}
+
+#[test]
+fn timeout_reset_postpones_firing() {
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = ComponentStream::with_timer(timer.clone(), || Timeout {
+        timeout: TimeoutCell::new(100 * MS),
+    });
+    let mut stream = stream.enter();
+
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    timer.advance(60 * MS);
+    stream.component_mut().timeout.reset(100 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    // Original deadline passed
+    timer.advance(60 * MS);
+    assert!(poll_changed(&mut stream).unwrap_or_default().is_empty());
+    assert!(!stream.component().timeout.is_elapsed());
+
+    timer.advance(40 * MS);
+    assert_eq!(poll_changed(&mut stream).unwrap(), ["timeout"]);
+    assert!(stream.component().timeout.is_elapsed());
+}
+
+#[test]
+fn cancelled_timeout_does_not_fire() {
+    let timer = Arc::new(ManualTimer::new());
+    let mut stream = ComponentStream::with_timer(timer.clone(), || Timeout {
+        timeout: TimeoutCell::new(100 * MS),
+    });
+    let mut stream = stream.enter();
+
+    assert_eq!(poll_changed(&mut stream).unwrap(), NOTHING);
+
+    stream.component_mut().timeout.cancel();
+    assert!(stream.component().timeout.is_elapsed());
+
+    timer.advance(100 * MS);
+    assert!(poll_changed(&mut stream).unwrap_or_default().is_empty());
+    assert!(poll_changed(&mut stream).is_none());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_timer_fires_timeout() {
+    use async_component::timer::TokioTimer;
+
+    let mut stream = ComponentStream::with_timer(Arc::new(TokioTimer::new()), || Timeout {
+        timeout: TimeoutCell::new(20 * MS),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().await.unwrap().is_empty());
+
+    let report = tokio::time::timeout(Duration::from_secs(5), stream.next())
+        .await
+        .expect("Timeout is not fired in time")
+        .unwrap();
+    assert_eq!(report.changed().collect::<Vec<_>>(), [["timeout"]]);
+}
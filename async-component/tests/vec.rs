@@ -0,0 +1,59 @@
+use async_component::{
+    context::ComponentStream,
+    vec::{StateVec, VecChange},
+    AsyncComponent,
+};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct List {
+    #[state(Self::on_change)]
+    items: StateVec<i32>,
+
+    changes: Vec<VecChange>,
+}
+
+impl List {
+    fn on_change(&mut self, changes: Vec<VecChange>) {
+        self.changes = changes;
+    }
+}
+
+#[test]
+fn retain_and_truncate_signal_once() {
+    let mut stream = ComponentStream::new(|| List {
+        items: StateVec::new((0..8).collect()),
+        changes: Vec::new(),
+    });
+    let mut stream = stream.enter();
+
+    assert!(stream.next().now_or_never().is_some());
+
+    stream.component_mut().items.retain(|item| item % 3 != 0);
+    assert_eq!(*stream.component().items, [1, 2, 4, 5, 7]);
+
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.signals(), 1);
+    assert_eq!(
+        stream.component().changes,
+        [
+            VecChange::Removed(0),
+            VecChange::Removed(2),
+            VecChange::Removed(4)
+        ]
+    );
+
+    stream.component_mut().items.truncate(2);
+    assert_eq!(*stream.component().items, [1, 2]);
+
+    let report = stream.next().now_or_never().flatten().unwrap();
+    assert_eq!(report.signals(), 1);
+    assert_eq!(
+        stream.component().changes,
+        [
+            VecChange::Removed(4),
+            VecChange::Removed(3),
+            VecChange::Removed(2)
+        ]
+    );
+}
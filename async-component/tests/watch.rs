@@ -0,0 +1,79 @@
+use std::{fs, thread, time::Duration};
+
+use async_component::{
+    context::{ComponentStream, EnteredComponentStream},
+    watch::{FileChange, FileEvent},
+    AsyncComponent, FileWatchCell,
+};
+use futures::{FutureExt, StreamExt};
+
+#[derive(AsyncComponent)]
+struct Watcher {
+    #[state(Self::on_event)]
+    watch: FileWatchCell,
+
+    events: Vec<FileEvent>,
+}
+
+impl Watcher {
+    fn on_event(&mut self, event: FileEvent) {
+        self.events.push(event);
+    }
+}
+
+/// Update stream until `count` events are received
+fn wait_events(stream: &mut EnteredComponentStream<Watcher>, count: usize) {
+    for _ in 0..1000 {
+        stream.next().now_or_never();
+
+        if stream.component().events.len() >= count {
+            return;
+        }
+
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    panic!("Events are not received in time");
+}
+
+#[test]
+fn polling_reports_create_replace_and_remove() {
+    let dir = std::env::temp_dir().join(format!("async-component-watch-{}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("watched");
+
+    let mut stream = ComponentStream::new(|| Watcher {
+        watch: FileWatchCell::with_interval([&path], Duration::from_millis(10)),
+        events: Vec::new(),
+    });
+    let mut stream = stream.enter();
+
+    fs::write(&path, "a").unwrap();
+    wait_events(&mut stream, 1);
+
+    // Replace with file of different length, so the change is seen regardless of timestamp precision
+    let temp_path = dir.join("watched.tmp");
+    fs::write(&temp_path, "replaced").unwrap();
+    fs::rename(&temp_path, &path).unwrap();
+    wait_events(&mut stream, 2);
+
+    fs::remove_file(&path).unwrap();
+    wait_events(&mut stream, 3);
+
+    let event = |change| FileEvent {
+        path: path.clone(),
+        change,
+    };
+    assert_eq!(
+        stream.component().events,
+        [
+            event(FileChange::Created),
+            event(FileChange::Modified),
+            event(FileChange::Removed)
+        ]
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -1,26 +1,53 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    pin::Pin,
+};
 
-use async_component_core::AsyncComponent;
+use async_component_core::{
+    context::{try_with_current_context, StateContext},
+    AsyncComponent,
+};
 
 #[derive(Debug)]
-pub struct BoxedComponent<T: ?Sized>(pub Box<T>);
+pub struct BoxedComponent<T: ?Sized>(pub Pin<Box<T>>);
+
+impl<T> BoxedComponent<T> {
+    pub fn new(component: T) -> Self {
+        Self(Box::pin(component))
+    }
+}
+
+impl<T: ?Sized> BoxedComponent<T> {
+    /// Pinned mutable reference to inner component
+    pub fn as_pin_mut(&mut self) -> Pin<&mut T> {
+        self.0.as_mut()
+    }
+}
+
+impl<T: ?Sized> From<Box<T>> for BoxedComponent<T> {
+    fn from(component: Box<T>) -> Self {
+        Self(Box::into_pin(component))
+    }
+}
 
 impl<T: ?Sized> Deref for BoxedComponent<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &*self.0
+        &self.0
     }
 }
 
-impl<T: ?Sized> DerefMut for BoxedComponent<T> {
+impl<T: ?Sized + Unpin> DerefMut for BoxedComponent<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut *self.0
+        try_with_current_context(StateContext::signal);
+
+        &mut self.0
     }
 }
 
 impl<T: ?Sized + AsyncComponent> AsyncComponent for BoxedComponent<T> {
-    fn update_component(&mut self) {
-        self.0.update_component()
+    fn update_component(self: Pin<&mut Self>) {
+        self.get_mut().0.as_mut().update_component()
     }
 }
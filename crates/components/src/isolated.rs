@@ -0,0 +1,60 @@
+use std::{ops::Deref, pin::Pin, task::Context};
+
+use async_component_core::{
+    context::{with_current_context, ComponentStream, EnteredComponentStream},
+    AsyncComponent,
+};
+use futures_core::Stream;
+
+/// Component which owns its own [`ComponentStream`].
+///
+/// Inner component is scheduled independently and wakes the parent only when the inner stream has update.
+/// Inner component must be modified using [`IsolatedComponent::enter`] so signals are sent to the inner stream.
+#[derive(Debug)]
+pub struct IsolatedComponent<C> {
+    stream: ComponentStream<C>,
+}
+
+impl<C: AsyncComponent> IsolatedComponent<C> {
+    /// Create new [`IsolatedComponent`]
+    pub fn new(func: impl FnOnce() -> C) -> Self {
+        Self {
+            stream: ComponentStream::new(func),
+        }
+    }
+
+    /// Enter context of inner stream
+    pub fn enter(&mut self) -> EnteredComponentStream<'_, C> {
+        self.stream.enter()
+    }
+
+    /// Modify pinned inner component in context of inner stream
+    pub fn modify_pinned<R>(&mut self, func: impl FnOnce(Pin<&mut C>) -> R) -> R {
+        func(self.stream.enter().component_mut().as_pin_mut())
+    }
+}
+
+impl<C: AsyncComponent + Unpin> IsolatedComponent<C> {
+    /// Modify inner component in context of inner stream
+    pub fn modify<R>(&mut self, func: impl FnOnce(&mut C) -> R) -> R {
+        func(&mut self.stream.enter().component_mut())
+    }
+}
+
+impl<C> Deref for IsolatedComponent<C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.stream.component()
+    }
+}
+
+impl<C: AsyncComponent> AsyncComponent for IsolatedComponent<C> {
+    fn update_component(self: Pin<&mut Self>) {
+        let waker = with_current_context(|cx| cx.task_context().waker().clone());
+
+        // Inner stream wakes the parent again if it has more update
+        let mut stream = self.get_mut().stream.enter();
+        let _ = Pin::new(&mut stream).poll_next(&mut Context::from_waker(&waker));
+    }
+}
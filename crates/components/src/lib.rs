@@ -1,6 +1,7 @@
 #![doc = include_str!("../README.md")]
 
 pub mod boxed;
+pub mod isolated;
 pub mod map;
 pub mod option;
 pub mod vec;
@@ -2,9 +2,13 @@ use std::{
     collections::{hash_map::RandomState, HashMap},
     hash::Hash,
     ops::{Deref, DerefMut},
+    pin::Pin,
 };
 
-use async_component_core::AsyncComponent;
+use async_component_core::{
+    context::{try_with_current_context, StateContext},
+    AsyncComponent,
+};
 
 #[derive(Debug)]
 pub struct HashMapComponent<K, V, S = RandomState>(pub HashMap<K, V, S>);
@@ -19,14 +23,19 @@ impl<K, V, S> Deref for HashMapComponent<K, V, S> {
 
 impl<K, V, S> DerefMut for HashMapComponent<K, V, S> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        try_with_current_context(StateContext::signal);
+
         &mut self.0
     }
 }
 
-impl<K: Eq + Hash, V: AsyncComponent, S> AsyncComponent for HashMapComponent<K, V, S> {
-    fn update_component(&mut self) {
-        for value in self.0.values_mut() {
-            value.update_component();
+impl<K, V, S> Unpin for HashMapComponent<K, V, S> {}
+
+/// Values are moved when [`HashMap`] grows, so they must be [`Unpin`]
+impl<K: Eq + Hash, V: AsyncComponent + Unpin, S> AsyncComponent for HashMapComponent<K, V, S> {
+    fn update_component(self: Pin<&mut Self>) {
+        for value in self.get_mut().0.values_mut() {
+            Pin::new(value).update_component();
         }
     }
 }
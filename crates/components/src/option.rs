@@ -1,6 +1,12 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    pin::Pin,
+};
 
-use async_component_core::AsyncComponent;
+use async_component_core::{
+    context::{try_with_current_context, StateContext},
+    AsyncComponent,
+};
 
 #[derive(Debug, Default)]
 pub struct OptionComponent<T>(pub Option<T>);
@@ -15,13 +21,19 @@ impl<T> Deref for OptionComponent<T> {
 
 impl<T> DerefMut for OptionComponent<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        // Inserted component is updated with the rest of the scope on next update
+        try_with_current_context(StateContext::signal);
+
         &mut self.0
     }
 }
 
 impl<T: AsyncComponent> AsyncComponent for OptionComponent<T> {
-    fn update_component(&mut self) {
-        if let Some(ref mut inner) = self.0 {
+    fn update_component(self: Pin<&mut Self>) {
+        // SAFETY: inner component is structurally pinned. [`OptionComponent`] is [`Unpin`] only if it is.
+        let inner = unsafe { self.map_unchecked_mut(|this| &mut this.0) };
+
+        if let Some(inner) = inner.as_pin_mut() {
             inner.update_component()
         }
     }
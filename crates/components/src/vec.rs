@@ -1,6 +1,12 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    pin::Pin,
+};
 
-use async_component_core::AsyncComponent;
+use async_component_core::{
+    context::{try_with_current_context, StateContext},
+    AsyncComponent,
+};
 
 #[derive(Debug)]
 pub struct VecComponent<T>(pub Vec<T>);
@@ -15,14 +21,19 @@ impl<T> Deref for VecComponent<T> {
 
 impl<T> DerefMut for VecComponent<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        try_with_current_context(StateContext::signal);
+
         &mut self.0
     }
 }
 
-impl<T: AsyncComponent> AsyncComponent for VecComponent<T> {
-    fn update_component(&mut self) {
-        for component in &mut self.0 {
-            component.update_component();
+impl<T> Unpin for VecComponent<T> {}
+
+/// Elements are moved when [`Vec`] grows, so they must be [`Unpin`]
+impl<T: AsyncComponent + Unpin> AsyncComponent for VecComponent<T> {
+    fn update_component(self: Pin<&mut Self>) {
+        for component in &mut self.get_mut().0 {
+            Pin::new(component).update_component();
         }
     }
 }
@@ -0,0 +1,181 @@
+use std::{pin::Pin, task::Poll};
+
+use futures_core::Stream;
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    State, StreamEvent,
+};
+
+/// State which drains every ready item of inner stream in one update.
+///
+/// Items are yielded as [`Vec`]. If maximum batch size is set, remaining items are drained on next update
+/// so one busy stream cannot starve other states of the component.
+/// [`StreamEvent::Ended`] is yielded on the update after the last batch.
+/// Like [`crate::StreamCell`], `!Unpin` stream needs `#[pin]` on the field.
+#[derive(Debug)]
+pub struct BatchStreamCell<T> {
+    inner: T,
+    max_batch: Option<usize>,
+
+    terminated: bool,
+    end_reported: bool,
+}
+
+impl<T: Stream> BatchStreamCell<T> {
+    /// Create new [`BatchStreamCell`] without batch size limit
+    pub fn new(inner: T) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            inner,
+            max_batch: None,
+
+            terminated: false,
+            end_reported: false,
+        }
+    }
+
+    /// Create new [`BatchStreamCell`] which yields at most `max_batch` items per update
+    pub fn with_max_batch(inner: T, max_batch: usize) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            inner,
+            max_batch: Some(max_batch.max(1)),
+
+            terminated: false,
+            end_reported: false,
+        }
+    }
+
+    /// Returns true if inner stream is terminated
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Replace inner stream with new one
+    pub fn set(&mut self, inner: T) {
+        self.inner = inner;
+        self.terminated = false;
+        self.end_reported = false;
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Maximum batch size
+    pub fn max_batch(&self) -> Option<usize> {
+        self.max_batch
+    }
+
+    /// Set maximum batch size. [`None`] removes the limit.
+    pub fn set_max_batch(&mut self, max_batch: Option<usize>) {
+        self.max_batch = max_batch.map(|max_batch| max_batch.max(1));
+    }
+
+    /// Replace inner stream of pinned [`BatchStreamCell`] with new one
+    pub fn set_pinned(self: Pin<&mut Self>, inner: T) {
+        let (mut stream, this) = self.project();
+
+        stream.set(inner);
+        *this.terminated = false;
+        *this.end_reported = false;
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut T>, Unpinned<'_>) {
+        // SAFETY: inner stream is structurally pinned and never moved out of pinned cell
+        unsafe {
+            let this = self.get_unchecked_mut();
+
+            (
+                Pin::new_unchecked(&mut this.inner),
+                Unpinned {
+                    max_batch: this.max_batch,
+                    terminated: &mut this.terminated,
+                    end_reported: &mut this.end_reported,
+                },
+            )
+        }
+    }
+}
+
+/// Fields of pinned [`BatchStreamCell`] other than inner stream
+struct Unpinned<'a> {
+    max_batch: Option<usize>,
+    terminated: &'a mut bool,
+    end_reported: &'a mut bool,
+}
+
+impl<T: Stream> State for BatchStreamCell<T> {
+    type Output = StreamEvent<Vec<T::Item>>;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let (mut stream, this) = this.project();
+
+        if *this.terminated {
+            if *this.end_reported {
+                return None;
+            }
+
+            *this.end_reported = true;
+            return Some(StreamEvent::Ended);
+        }
+
+        with_current_context(|cx| {
+            let mut items = Vec::new();
+
+            loop {
+                if this.max_batch == Some(items.len()) {
+                    // Drain rest on next update
+                    cx.signal();
+                    break;
+                }
+
+                match stream.as_mut().poll_next(&mut cx.task_context()) {
+                    Poll::Ready(Some(item)) => items.push(item),
+
+                    Poll::Ready(None) => {
+                        *this.terminated = true;
+                        break;
+                    }
+
+                    Poll::Pending => break,
+                }
+            }
+
+            if !items.is_empty() {
+                if *this.terminated {
+                    // Report end on next update
+                    cx.signal();
+                }
+
+                Some(StreamEvent::Item(items))
+            } else if *this.terminated {
+                *this.end_reported = true;
+                Some(StreamEvent::Ended)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<T: Stream> From<T> for BatchStreamCell<T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T: Stream + Default> Default for BatchStreamCell<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> Drop for BatchStreamCell<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,229 @@
+//! State computed on worker thread pool
+
+use std::{
+    num::NonZeroUsize,
+    ops::Deref,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Sender},
+        Arc, Mutex, OnceLock, Weak,
+    },
+    thread,
+};
+
+use atomic_waker::AtomicWaker;
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Run job on worker thread pool shared by every [`BlockingCell`]
+pub(crate) fn submit(job: Job) {
+    static POOL: OnceLock<Sender<Job>> = OnceLock::new();
+
+    POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        // At least two workers, so single long job does not block every other cell
+        let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get).max(2);
+
+        for _ in 0..workers {
+            let receiver = receiver.clone();
+
+            thread::Builder::new()
+                .name("async-component-blocking".into())
+                .spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+
+                    match job {
+                        // Jobs of BlockingCell catch their own panic to report it
+                        Ok(job) => {
+                            panic::catch_unwind(AssertUnwindSafe(job)).ok();
+                        }
+
+                        Err(_) => break,
+                    }
+                })
+                .expect("Cannot spawn blocking worker thread");
+        }
+
+        sender
+    })
+    .send(job)
+    .ok();
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: AtomicUsize,
+    running: AtomicUsize,
+    /// Latest finished result tagged with generation of its job
+    result: Mutex<Option<(usize, thread::Result<T>)>>,
+    waker: AtomicWaker,
+}
+
+/// Finish job even if it panicked
+struct JobGuard<'a, T>(&'a Slot<T>);
+
+impl<T> Drop for JobGuard<'_, T> {
+    fn drop(&mut self) {
+        self.0.running.fetch_sub(1, Ordering::AcqRel);
+        self.0.waker.wake();
+    }
+}
+
+/// State which computes value on worker thread pool.
+///
+/// Previous result is kept while computing and new result is reported when it is ready.
+/// Results are applied in submission order, so result finishing after newer one is discarded.
+/// If a job panics, the panic is resumed on the update which would apply its result.
+#[derive(Debug)]
+pub struct BlockingCell<T> {
+    changed: bool,
+    value: Option<T>,
+    value_generation: usize,
+    cancel_superseded: bool,
+    slot: Arc<Slot<T>>,
+    tracked: Tracked,
+}
+
+impl<T> BlockingCell<T> {
+    /// Create new [`BlockingCell`].
+    /// Every submitted job runs to completion, but result older than current value is discarded.
+    pub fn new() -> Self {
+        Self::with_cancel(false)
+    }
+
+    /// Create new [`BlockingCell`] which cancels superseded jobs.
+    /// Jobs which are not started yet are skipped and only result of latest job is reported.
+    pub fn cancelling() -> Self {
+        Self::with_cancel(true)
+    }
+
+    fn with_cancel(cancel_superseded: bool) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            changed: true,
+            value: None,
+            value_generation: 0,
+            cancel_superseded,
+            slot: Arc::new(Slot {
+                generation: AtomicUsize::new(0),
+                running: AtomicUsize::new(0),
+                result: Mutex::new(None),
+                waker: AtomicWaker::new(),
+            }),
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Returns true if any job is running or waiting to be run
+    pub fn is_running(this: &Self) -> bool {
+        this.slot.running.load(Ordering::Acquire) > 0
+    }
+}
+
+impl<T: Send + 'static> BlockingCell<T> {
+    /// Run `func` on worker thread pool
+    pub fn run(this: &mut Self, func: impl FnOnce() -> T + Send + 'static) {
+        let generation = this.slot.generation.fetch_add(1, Ordering::AcqRel) + 1;
+        this.slot.running.fetch_add(1, Ordering::AcqRel);
+
+        let cancel_superseded = this.cancel_superseded;
+        let slot = Arc::downgrade(&this.slot);
+
+        submit(Box::new(move || {
+            let slot = match Weak::upgrade(&slot) {
+                Some(slot) => slot,
+                None => return,
+            };
+            let _guard = JobGuard(&slot);
+
+            let superseded = || slot.generation.load(Ordering::Acquire) != generation;
+
+            if cancel_superseded && superseded() {
+                return;
+            }
+
+            let output = panic::catch_unwind(AssertUnwindSafe(func));
+
+            let mut result = slot.result.lock().unwrap();
+            if cancel_superseded && superseded() {
+                return;
+            }
+
+            if result
+                .as_ref()
+                .is_none_or(|(latest, _)| *latest < generation)
+            {
+                *result = Some((generation, output));
+            }
+        }));
+    }
+}
+
+impl<T> Default for BlockingCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for BlockingCell<T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.value
+    }
+}
+
+impl<T> Unpin for BlockingCell<T> {}
+
+impl<T> State for BlockingCell<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| this.slot.waker.register(cx.task_context().waker()));
+
+        let result = this.slot.result.lock().unwrap().take();
+        if let Some((generation, output)) = result {
+            if generation > this.value_generation {
+                this.value_generation = generation;
+
+                match output {
+                    Ok(value) => {
+                        this.value = Some(value);
+                        this.changed = true;
+                        this.tracked.invalidate();
+                    }
+
+                    Err(payload) => panic::resume_unwind(payload),
+                }
+            }
+        }
+
+        if this.changed {
+            this.changed = false;
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Drop for BlockingCell<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -1,86 +1,241 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
+    ops::{Deref, DerefMut},
     pin::Pin,
+    rc::Rc,
     sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock, Weak,
     },
     task::{Context, Poll, Wake, Waker},
+    time::Instant,
 };
 
 use atomic_waker::AtomicWaker;
 use futures_core::Stream;
 
-use crate::AsyncComponent;
+use crate::{
+    event::{EventComponent, Events},
+    report::{self, UpdateReport},
+    timer::{ThreadTimer, Timer},
+    AsyncComponent,
+};
 
 thread_local! {
-    static CONTEXT: RefCell<Option<StateContext>> = RefCell::new(None);
+    static CONTEXT: RefCell<Vec<(usize, Rc<StateContext>)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_CONTEXT_ID: Cell<usize> = const { Cell::new(0) };
+
+    static BATCH: RefCell<Batch> = RefCell::new(Batch::default());
 }
 
 pub fn with_current_context<R>(func: impl FnOnce(&StateContext) -> R) -> R {
-    CONTEXT.with(|cx| match *cx.borrow() {
-        Some(ref cx) => func(cx),
-        None => panic!("Called without state context"),
-    })
+    try_with_current_context(func).expect("Called without state context")
 }
 
+/// Call `func` with current context.
+/// Returns [`None`] if called without state context.
+///
+/// Current context is the context of innermost entered [`ComponentStream`].
+pub fn try_with_current_context<R>(func: impl FnOnce(&StateContext) -> R) -> Option<R> {
+    // Release borrow before calling `func`, since it can enter another context
+    let cx = CONTEXT.with(|cx| cx.borrow().last().map(|(_, cx)| cx.clone()))?;
+
+    Some(func(&cx))
+}
+
+/// Current time of [`Timer`] of current context.
+/// Falls back to [`Instant::now`] if called without state context.
+pub(crate) fn current_time() -> Instant {
+    try_with_current_context(|cx| cx.timer().now()).unwrap_or_else(Instant::now)
+}
+
+/// Exits entered context when dropped
+#[doc(hidden)]
 #[derive(Debug)]
-struct EnterContextGuard {}
+pub struct EnterContextGuard {
+    id: usize,
+}
 
 impl Drop for EnterContextGuard {
     fn drop(&mut self) {
-        CONTEXT.with(|cell| {
-            *cell.borrow_mut() = None;
-        })
+        let entry = CONTEXT.with(|cell| {
+            let mut stack = cell.borrow_mut();
+
+            // Guards can be dropped in any order, so remove its own entry instead of the top one
+            let index = stack.iter().rposition(|(id, _)| *id == self.id)?;
+            Some(stack.remove(index))
+        });
+
+        drop(entry);
     }
 }
 
-fn enter_guarded(cx: StateContext) -> EnterContextGuard {
+/// Enter `cx` until the guard is dropped
+pub(crate) fn enter_guarded(cx: StateContext) -> EnterContextGuard {
+    let id = NEXT_CONTEXT_ID.with(|next| next.replace(next.get().wrapping_add(1)));
+
     CONTEXT.with(|cell| {
-        {
-            let mut cell = cell.borrow_mut();
+        cell.borrow_mut().push((id, Rc::new(cx)));
+
+        EnterContextGuard { id }
+    })
+}
+
+/// Enter scope of child component at `index` of current component.
+/// Returns [`None`] if the child component can be skipped since nothing in it was signaled since last update.
+#[doc(hidden)]
+pub fn enter_scope(index: usize) -> Option<EnterContextGuard> {
+    with_current_context(|cx| cx.child_scope(index)).map(enter_guarded)
+}
 
-            if cell.is_some() {
-                panic!("State context is already set");
+#[derive(Default)]
+struct Batch {
+    depth: usize,
+    wakers: Vec<Waker>,
+    deferred: Vec<Box<dyn FnOnce()>>,
+}
+
+#[derive(Debug)]
+struct BatchGuard {}
+
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        let (wakers, deferred) = BATCH.with(|batch| {
+            let mut batch = batch.borrow_mut();
+            batch.depth -= 1;
+
+            if batch.depth == 0 {
+                (
+                    std::mem::take(&mut batch.wakers),
+                    std::mem::take(&mut batch.deferred),
+                )
+            } else {
+                Default::default()
             }
+        });
 
-            *cell = Some(cx);
+        for func in deferred {
+            func();
         }
 
-        EnterContextGuard {}
-    })
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// Run closure in batch.
+///
+/// Signals sent in the batch are deduplicated and delivered once when the outermost batch ends,
+/// so an update observes either none or all of the changes made in the batch.
+/// Batches can be nested.
+pub fn batch<R>(func: impl FnOnce() -> R) -> R {
+    BATCH.with(|batch| batch.borrow_mut().depth += 1);
+    let _guard = BatchGuard {};
+
+    func()
+}
+
+/// Wake `waker`, or postpone it until the outermost batch ends
+pub(crate) fn wake_batched(waker: &Waker) {
+    let woken = BATCH.with(|batch| {
+        let mut batch = batch.borrow_mut();
+        if batch.depth == 0 {
+            return false;
+        }
+
+        if !batch.wakers.iter().any(|pending| pending.will_wake(waker)) {
+            batch.wakers.push(waker.clone());
+        }
+
+        true
+    });
+
+    if !woken {
+        waker.wake_by_ref();
+    }
+}
+
+/// Run `func`, or postpone it until the outermost batch ends
+pub(crate) fn defer_batched(func: impl FnOnce() + 'static) {
+    let func = BATCH.with(|batch| {
+        let mut batch = batch.borrow_mut();
+        if batch.depth == 0 {
+            return Some(func);
+        }
+
+        batch.deferred.push(Box::new(func));
+        None
+    });
+
+    if let Some(func) = func {
+        func();
+    }
 }
 
 #[derive(Debug)]
 pub struct ComponentStream<C> {
-    inner: Arc<Inner>,
-    component: C,
+    scope: Arc<Scope>,
+    timer: Arc<dyn Timer>,
+    component: Pin<Box<C>>,
 }
 
 impl<C: AsyncComponent> ComponentStream<C> {
-    /// Create new [`ComponentStream`]
+    /// Create new [`ComponentStream`] using [`Timer`] of current context.
+    /// [`ThreadTimer`] is used if called without state context.
     pub fn new(func: impl FnOnce() -> C) -> Self {
-        let inner = Arc::new(Inner::default());
+        let timer = try_with_current_context(|cx| cx.timer.clone())
+            .unwrap_or_else(|| Arc::new(ThreadTimer));
+
+        Self::with_timer(timer, func)
+    }
+
+    /// Create new [`ComponentStream`] using given [`Timer`]
+    pub fn with_timer(timer: Arc<dyn Timer>, func: impl FnOnce() -> C) -> Self {
+        let scope = Arc::new(Scope::new(Arc::new(Inner::default()), Weak::new()));
 
         let component = {
-            let _guard = enter_guarded(StateContext::new(Waker::from(inner.clone())));
+            let _guard = enter_guarded(StateContext::new(&scope, timer.clone()));
 
-            func()
+            Box::pin(func())
         };
 
-        Self { inner, component }
+        Self {
+            scope,
+            timer,
+            component,
+        }
     }
 
-    /// Enter context scope with stream
+    /// Enter context scope with stream.
+    /// Streams can be entered while other stream is entered, and inner one becomes current context until it is exited.
+    ///
+    /// Updates and [`EnteredComponentStream::component_mut`] always run in context of their own stream,
+    /// even if other stream is entered after this one.
     pub fn enter<'a>(&'a mut self) -> EnteredComponentStream<'a, C> {
         EnteredComponentStream {
-            _guard: enter_guarded(StateContext::new(Waker::from(self.inner.clone()))),
+            _guard: self.enter_context(),
             stream: self,
         }
     }
 }
 
-impl<C: AsyncComponent> Unpin for ComponentStream<C> {}
+impl<C> ComponentStream<C> {
+    /// Component of the stream
+    pub fn component(&self) -> &C {
+        &self.component
+    }
+
+    fn context(&self) -> StateContext {
+        StateContext::new(&self.scope, self.timer.clone())
+    }
+
+    fn enter_context(&self) -> EnterContextGuard {
+        enter_guarded(self.context())
+    }
+}
+
+impl<C> Unpin for ComponentStream<C> {}
 
 #[derive(Debug)]
 pub struct EnteredComponentStream<'a, C> {
@@ -93,48 +248,168 @@ impl<C> EnteredComponentStream<'_, C> {
         &self.stream.component
     }
 
-    pub fn component_mut(&mut self) -> &mut C {
-        &mut self.stream.component
+    /// Mutable access to component which enters context of the stream until it is dropped
+    pub fn component_mut(&mut self) -> ComponentMut<'_, C> {
+        ComponentMut {
+            _guard: self.stream.enter_context(),
+            component: self.stream.component.as_mut(),
+        }
+    }
+}
+
+impl<'a, C: EventComponent> EnteredComponentStream<'a, C> {
+    /// Take events emitted by component since last call
+    pub fn take_events(&mut self) -> Vec<C::Event> {
+        self.stream.component.as_mut().take_events()
+    }
+
+    /// Convert into [`Events`] stream which yields events of component
+    pub fn events(self) -> Events<'a, C> {
+        Events::new(self)
     }
 }
 
 impl<C: AsyncComponent> Stream for EnteredComponentStream<'_, C> {
-    type Item = ();
+    type Item = UpdateReport;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<UpdateReport>> {
+        let scope = &self.stream.scope;
+        scope.inner.waker.register(cx.waker());
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<()>> {
-        self.stream.inner.waker.register(cx.waker());
+        match scope.inner.signals.swap(0, Ordering::SeqCst) {
+            0 => Poll::Pending,
 
-        if self.stream.inner.updated.swap(false, Ordering::Relaxed) {
-            self.stream.component.update_component();
-            Poll::Ready(Some(()))
-        } else {
-            Poll::Pending
+            signals => {
+                let _guard = enter_guarded(StateContext {
+                    waker: Waker::from(scope.clone()),
+                    updating: true,
+                    deep: scope.deep.swap(false, Ordering::SeqCst),
+                    ..self.stream.context()
+                });
+                Poll::Ready(Some(report::record(signals, || {
+                    self.stream.component.as_mut().update_component()
+                })))
+            }
         }
     }
 }
 
+/// Mutable reference of component returned by [`EnteredComponentStream::component_mut`].
+///
+/// Signals sent while it is alive are delivered to the stream owning the component.
+#[derive(Debug)]
+pub struct ComponentMut<'a, C> {
+    _guard: EnterContextGuard,
+    component: Pin<&'a mut C>,
+}
+
+impl<C> ComponentMut<'_, C> {
+    /// Pinned component
+    pub fn as_pin_mut(&mut self) -> Pin<&mut C> {
+        self.component.as_mut()
+    }
+}
+
+impl<C> Deref for ComponentMut<'_, C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.component
+    }
+}
+
+impl<C: Unpin> DerefMut for ComponentMut<'_, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.component
+    }
+}
+
+/// Context of component.
+///
+/// Each `#[component]` field of derived component is updated in its own context,
+/// and the field is skipped on update unless its context is signaled since last update.
+/// Wakers of the context wake only scopes on the path to the component,
+/// while [`StateContext::signal`] updates every component under the component.
+/// Context outside of update signals every component of the stream.
+///
+/// Component moved into `#[component]` field during update is not updated until the field is signaled.
 #[derive(Debug, Clone)]
-pub struct StateContext(Waker);
+pub struct StateContext {
+    scope: Arc<Scope>,
+    waker: Waker,
+    deep_waker: Waker,
+    timer: Arc<dyn Timer>,
+    updating: bool,
+    deep: bool,
+}
 
 impl StateContext {
-    pub(crate) const fn new(waker: Waker) -> Self {
-        StateContext(waker)
+    /// Context of the root component used outside of update
+    fn new(scope: &Arc<Scope>, timer: Arc<dyn Timer>) -> Self {
+        let deep_waker = scope.deep_waker();
+
+        StateContext {
+            scope: scope.clone(),
+            waker: deep_waker.clone(),
+            deep_waker,
+            timer,
+            updating: false,
+            deep: true,
+        }
+    }
+
+    /// Context of child component at `index` if it needs update
+    fn child_scope(&self, index: usize) -> Option<Self> {
+        let (scope, created) = self.scope.child(index);
+
+        // Take both flags, since the child is updated either way
+        let path = scope.path.swap(false, Ordering::SeqCst);
+        let deep = self.deep | created | scope.deep.swap(false, Ordering::SeqCst);
+
+        if !(path || deep) {
+            return None;
+        }
+
+        Some(StateContext {
+            waker: Waker::from(scope.clone()),
+            deep_waker: scope.deep_waker(),
+            scope,
+            timer: self.timer.clone(),
+            updating: self.updating,
+            deep,
+        })
     }
 
-    /// Signal context to wake
+    /// Returns true if component of this context is being updated
+    pub fn is_updating(&self) -> bool {
+        self.updating
+    }
+
+    /// Signal context to wake.
+    /// Delivered when the outermost batch ends if called in [`batch`].
     pub fn signal(&self) {
-        self.0.wake_by_ref();
+        wake_batched(&self.deep_waker);
     }
 
     /// Returns [`Context`] which can be used for polling future
     pub fn task_context<'a>(&'a self) -> Context<'a> {
-        Context::from_waker(&self.0)
+        Context::from_waker(&self.waker)
+    }
+
+    /// Returns [`Timer`] of the executor
+    pub fn timer(&self) -> &dyn Timer {
+        &*self.timer
+    }
+
+    /// Signal context when `deadline` is reached
+    pub fn signal_at(&self, deadline: Instant) {
+        self.timer.register(deadline, self.waker.clone());
     }
 }
 
 #[derive(Debug)]
 struct Inner {
-    updated: AtomicBool,
+    signals: AtomicUsize,
     waker: AtomicWaker,
 }
 
@@ -144,7 +419,7 @@ impl Wake for Inner {
     }
 
     fn wake_by_ref(self: &Arc<Self>) {
-        self.updated.store(true, Ordering::Relaxed);
+        self.signals.fetch_add(1, Ordering::SeqCst);
         self.waker.wake()
     }
 }
@@ -152,8 +427,111 @@ impl Wake for Inner {
 impl Default for Inner {
     fn default() -> Self {
         Self {
-            updated: AtomicBool::new(true),
+            signals: AtomicUsize::new(1),
             waker: Default::default(),
         }
     }
 }
+
+/// Dirty flags of component in the tree of stream.
+///
+/// Child scopes are indexed by field index and kept until the stream is dropped.
+/// Waking the scope marks path from the root to the scope, so only components on the path are updated.
+#[derive(Debug)]
+struct Scope {
+    inner: Arc<Inner>,
+    parent: Weak<Scope>,
+
+    /// Component or one of its children is signaled
+    path: AtomicBool,
+
+    /// Every component under the scope is signaled
+    deep: AtomicBool,
+
+    children: Mutex<Vec<Option<Arc<Scope>>>>,
+    deep_waker: OnceLock<Waker>,
+}
+
+impl Scope {
+    fn new(inner: Arc<Inner>, parent: Weak<Scope>) -> Self {
+        Self {
+            inner,
+            parent,
+            path: AtomicBool::new(false),
+            deep: AtomicBool::new(true),
+            children: Mutex::new(Vec::new()),
+            deep_waker: OnceLock::new(),
+        }
+    }
+
+    /// Child scope at `index`, and whether it is newly created
+    fn child(self: &Arc<Self>, index: usize) -> (Arc<Scope>, bool) {
+        let mut children = self.children.lock().unwrap();
+        if children.len() <= index {
+            children.resize(index + 1, None);
+        }
+
+        match children[index] {
+            Some(ref child) => (child.clone(), false),
+
+            None => {
+                let child = Arc::new(Scope::new(self.inner.clone(), Arc::downgrade(self)));
+                children[index] = Some(child.clone());
+
+                (child, true)
+            }
+        }
+    }
+
+    /// Waker which marks every component under the scope
+    fn deep_waker(self: &Arc<Self>) -> Waker {
+        self.deep_waker
+            .get_or_init(|| Waker::from(Arc::new(DeepWake(Arc::downgrade(self)))))
+            .clone()
+    }
+
+    fn wake_path(&self) {
+        // Mark from the scope to the root, so the flag of child is set before its parent is checked
+        if !self.path.swap(true, Ordering::SeqCst) {
+            let mut parent = self.parent.upgrade();
+
+            while let Some(scope) = parent {
+                if scope.path.swap(true, Ordering::SeqCst) {
+                    break;
+                }
+
+                parent = scope.parent.upgrade();
+            }
+        }
+
+        self.inner.wake_by_ref();
+    }
+}
+
+impl Wake for Scope {
+    fn wake(self: Arc<Self>) {
+        self.wake_path()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_path()
+    }
+}
+
+/// Waker of [`StateContext::signal`].
+/// Holds weak reference since the scope owns the waker.
+#[derive(Debug)]
+struct DeepWake(Weak<Scope>);
+
+impl Wake for DeepWake {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if let Some(scope) = self.0.upgrade() {
+            scope.deep.store(true, Ordering::SeqCst);
+            scope.wake_path();
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! States which limit rate of change reports using [`crate::timer::Timer`]
+
+use std::{
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    context::{current_time, try_with_current_context, with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+/// [`crate::StateCell`] which reports change only after value stays unchanged for `delay`.
+///
+/// Every mutable dereference postpones the report.
+#[derive(Debug)]
+pub struct DebounceCell<T> {
+    inner: T,
+    delay: Duration,
+    deadline: Option<Instant>,
+    registered: Option<Instant>,
+    tracked: Tracked,
+}
+
+impl<T> DebounceCell<T> {
+    /// Create new [`DebounceCell`].
+    /// Initial value is reported without delay.
+    pub fn new(inner: T, delay: Duration) -> Self {
+        try_with_current_context(StateContext::signal);
+        let now = current_time();
+
+        Self {
+            inner,
+            delay,
+            deadline: Some(now),
+            registered: None,
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Quiet period required before reporting change
+    pub fn delay(this: &Self) -> Duration {
+        this.delay
+    }
+
+    /// Returns true if a change is waiting to be reported
+    pub fn is_pending(this: &Self) -> bool {
+        this.deadline.is_some()
+    }
+
+    /// Invalidate this [`DebounceCell`] and postpone report.
+    /// Send signal to context.
+    pub fn invalidate(this: &mut Self) {
+        this.deadline = Some(current_time() + this.delay);
+        try_with_current_context(StateContext::signal);
+
+        this.tracked.invalidate();
+    }
+
+    /// Report pending change on next update without waiting
+    pub fn flush(this: &mut Self) {
+        if this.deadline.is_some() {
+            this.deadline = Some(current_time());
+            try_with_current_context(StateContext::signal);
+        }
+    }
+}
+
+impl<T> Deref for DebounceCell<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for DebounceCell<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        DebounceCell::invalidate(self);
+
+        &mut self.inner
+    }
+}
+
+impl<T> Unpin for DebounceCell<T> {}
+
+impl<T> State for DebounceCell<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        let deadline = this.deadline?;
+
+        with_current_context(|cx| {
+            if cx.timer().now() >= deadline {
+                this.deadline = None;
+                this.registered = None;
+
+                Some(())
+            } else {
+                if this.registered != Some(deadline) {
+                    this.registered = Some(deadline);
+                    cx.signal_at(deadline);
+                }
+
+                None
+            }
+        })
+    }
+}
+
+impl<T> Drop for DebounceCell<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+/// [`crate::StateCell`] which reports change at most once per `interval`.
+///
+/// Changes made during interval are reported together when the interval ends.
+#[derive(Debug)]
+pub struct ThrottleCell<T> {
+    inner: T,
+    interval: Duration,
+    pending: bool,
+    last_report: Option<Instant>,
+    registered: Option<Instant>,
+    tracked: Tracked,
+}
+
+impl<T> ThrottleCell<T> {
+    /// Create new [`ThrottleCell`].
+    /// Initial value is reported without delay.
+    pub fn new(inner: T, interval: Duration) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            inner,
+            interval,
+            pending: true,
+            last_report: None,
+            registered: None,
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Minimum interval between reports
+    pub fn interval(this: &Self) -> Duration {
+        this.interval
+    }
+
+    /// Returns true if a change is waiting to be reported
+    pub fn is_pending(this: &Self) -> bool {
+        this.pending
+    }
+
+    /// Invalidate this [`ThrottleCell`].
+    /// Send signal to context.
+    pub fn invalidate(this: &mut Self) {
+        this.pending = true;
+        this.tracked.invalidate();
+
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+impl<T> Deref for ThrottleCell<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for ThrottleCell<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        ThrottleCell::invalidate(self);
+
+        &mut self.inner
+    }
+}
+
+impl<T> Unpin for ThrottleCell<T> {}
+
+impl<T> State for ThrottleCell<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        if !this.pending {
+            return None;
+        }
+
+        with_current_context(|cx| {
+            let now = cx.timer().now();
+
+            match this.last_report.map(|last_report| last_report + this.interval) {
+                Some(next_report) if now < next_report => {
+                    if this.registered != Some(next_report) {
+                        this.registered = Some(next_report);
+                        cx.signal_at(next_report);
+                    }
+
+                    None
+                }
+
+                _ => {
+                    this.pending = false;
+                    this.last_report = Some(now);
+                    this.registered = None;
+
+                    Some(())
+                }
+            }
+        })
+    }
+}
+
+impl<T> Drop for ThrottleCell<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,118 @@
+//! Typed events emitted from component to the host
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{
+    context::{try_with_current_context, EnteredComponentStream},
+    AsyncComponent,
+};
+
+/// Component which emits typed events to the host.
+///
+/// Derived using `#[event]` attribute on [`EventQueue`] field.
+/// Events of `#[component]` fields are not forwarded, so parent must take and re-emit them if needed.
+pub trait EventComponent: AsyncComponent {
+    type Event;
+
+    /// Take events emitted since last call
+    fn take_events(self: Pin<&mut Self>) -> Vec<Self::Event>;
+}
+
+/// Queue of events emitted by component
+#[derive(Debug)]
+pub struct EventQueue<E> {
+    events: Vec<E>,
+}
+
+impl<E> EventQueue<E> {
+    /// Create new [`EventQueue`]
+    pub const fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    /// Emit event to the host.
+    /// Send signal to context unless emitted during update, since events are taken after the update.
+    pub fn emit(&mut self, event: E) {
+        self.events.push(event);
+
+        try_with_current_context(|cx| {
+            if !cx.is_updating() {
+                cx.signal();
+            }
+        });
+    }
+
+    /// Returns true if there are no events waiting to be taken
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Take every emitted event
+    pub fn take(&mut self) -> Vec<E> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl<E> Unpin for EventQueue<E> {}
+
+impl<E> Default for EventQueue<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+/// Event type of [`EventQueue`] used by derive
+pub trait QueueEvent {
+    type Event;
+}
+
+impl<E> QueueEvent for EventQueue<E> {
+    type Event = E;
+}
+
+/// Stream which yields events of component.
+///
+/// Updates without event are skipped.
+#[derive(Debug)]
+pub struct Events<'a, C> {
+    stream: EnteredComponentStream<'a, C>,
+}
+
+impl<'a, C> Events<'a, C> {
+    pub(crate) const fn new(stream: EnteredComponentStream<'a, C>) -> Self {
+        Self { stream }
+    }
+
+    /// Returns inner stream
+    pub fn into_inner(self) -> EnteredComponentStream<'a, C> {
+        self.stream
+    }
+}
+
+impl<C: EventComponent> Stream for Events<'_, C> {
+    type Item = Vec<C::Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.stream).poll_next(cx) {
+                Poll::Ready(Some(_)) => {
+                    let events = self.stream.take_events();
+
+                    if !events.is_empty() {
+                        return Poll::Ready(Some(events));
+                    }
+                }
+
+                Poll::Ready(None) => return Poll::Ready(None),
+
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
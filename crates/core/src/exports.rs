@@ -1 +1,7 @@
 pub use futures_core::Stream;
+
+pub use crate::context::{enter_scope, EnterContextGuard};
+pub use crate::event::QueueEvent;
+pub use crate::hook::{ByPin, ByRef, ComponentHook, StateHook};
+pub use crate::memo::store_computed;
+pub use crate::report::{enter_component, record_changed, ComponentPathGuard};
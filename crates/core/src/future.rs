@@ -0,0 +1,90 @@
+use std::{future::Future, pin::Pin, task::Poll};
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    State,
+};
+
+/// State which polls inner future and yields its output once.
+///
+/// `async` blocks and other `!Unpin` futures can be held directly in a `#[pin]` field.
+#[derive(Debug)]
+pub struct FutureCell<F> {
+    inner: Option<F>,
+}
+
+impl<F: Future> FutureCell<F> {
+    /// Create new [`FutureCell`]
+    pub fn new(inner: F) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self { inner: Some(inner) }
+    }
+
+    /// Returns true if inner future is not completed yet
+    pub fn is_pending(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Returns true if inner future is completed and its output was yielded
+    pub fn is_completed(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    /// Replace inner future with new one.
+    /// Previous future is dropped even if it is not completed.
+    pub fn reset(&mut self, inner: F) {
+        self.inner = Some(inner);
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Replace inner future of pinned [`FutureCell`] with new one.
+    /// Previous future is dropped in place even if it is not completed.
+    pub fn reset_pinned(self: Pin<&mut Self>, inner: F) {
+        self.project().set(Some(inner));
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    fn project(self: Pin<&mut Self>) -> Pin<&mut Option<F>> {
+        // SAFETY: inner future is structurally pinned and only dropped in place
+        unsafe { self.map_unchecked_mut(|this| &mut this.inner) }
+    }
+}
+
+impl<F: Future> State for FutureCell<F> {
+    type Output = F::Output;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let mut inner = this.project();
+        let future = inner.as_mut().as_pin_mut()?;
+
+        match with_current_context(|cx| future.poll(&mut cx.task_context())) {
+            Poll::Ready(output) => {
+                inner.set(None);
+                Some(output)
+            }
+
+            Poll::Pending => None,
+        }
+    }
+}
+
+impl<F: Future> From<F> for FutureCell<F> {
+    fn from(inner: F) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<F: Future + Default> Default for FutureCell<F> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<F> Drop for FutureCell<F> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
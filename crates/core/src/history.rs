@@ -0,0 +1,272 @@
+//! State with undo / redo history
+
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+};
+
+use crate::{
+    context::{try_with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+/// [`crate::StateCell`] which records every committed mutation and supports undo / redo.
+///
+/// Every mutable dereference records previous value as one history entry.
+/// Mutations between [`HistoryCell::begin`] and [`HistoryCell::commit`] are grouped into one entry.
+#[derive(Debug)]
+pub struct HistoryCell<T> {
+    changed: bool,
+    inner: T,
+    tracked: Tracked,
+
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    limit: Option<usize>,
+
+    transaction_depth: usize,
+    transaction_recorded: bool,
+}
+
+impl<T: Clone> HistoryCell<T> {
+    /// Create new [`HistoryCell`] with unbounded history
+    pub fn new(inner: T) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            changed: true,
+            inner,
+            tracked: Tracked::new(),
+
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit: None,
+
+            transaction_depth: 0,
+            transaction_recorded: false,
+        }
+    }
+
+    /// Create new [`HistoryCell`] which keeps at most `limit` undo entries
+    pub fn with_limit(inner: T, limit: usize) -> Self {
+        let mut cell = Self::new(inner);
+        cell.limit = Some(limit);
+
+        cell
+    }
+
+    /// Set maximum number of undo entries. [`None`] removes the limit.
+    /// Oldest entries are discarded if exceeded.
+    pub fn set_limit(this: &mut Self, limit: Option<usize>) {
+        this.limit = limit;
+        this.trim();
+    }
+
+    /// Start transaction.
+    /// Mutations until matching [`HistoryCell::commit`] are recorded as one entry.
+    /// Transactions can be nested.
+    pub fn begin(this: &mut Self) {
+        this.transaction_depth += 1;
+    }
+
+    /// End transaction started with [`HistoryCell::begin`]
+    pub fn commit(this: &mut Self) {
+        this.transaction_depth = this.transaction_depth.saturating_sub(1);
+
+        if this.transaction_depth == 0 {
+            this.transaction_recorded = false;
+        }
+    }
+
+    /// Set inner value and record previous one
+    pub fn set(this: &mut Self, value: T) {
+        **this = value;
+    }
+
+    /// Restore previous value.
+    /// Open transactions are committed.
+    ///
+    /// Returns false if there is nothing to undo.
+    pub fn undo(this: &mut Self) -> bool {
+        this.end_transactions();
+
+        match this.undo.pop_back() {
+            Some(value) => {
+                let current = std::mem::replace(&mut this.inner, value);
+                this.redo.push(current);
+
+                HistoryCell::invalidate(this);
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    /// Restore value reverted by [`HistoryCell::undo`].
+    /// Open transactions are committed.
+    ///
+    /// Returns false if there is nothing to redo.
+    pub fn redo(this: &mut Self) -> bool {
+        this.end_transactions();
+
+        match this.redo.pop() {
+            Some(value) => {
+                let current = std::mem::replace(&mut this.inner, value);
+                this.undo.push_back(current);
+                this.trim();
+
+                HistoryCell::invalidate(this);
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    /// Returns true if undo is available
+    pub fn can_undo(this: &Self) -> bool {
+        !this.undo.is_empty()
+    }
+
+    /// Returns true if redo is available
+    pub fn can_redo(this: &Self) -> bool {
+        !this.redo.is_empty()
+    }
+
+    /// Discard every undo / redo entries
+    pub fn clear_history(this: &mut Self) {
+        this.undo.clear();
+        this.redo.clear();
+    }
+
+    /// Invalidate this [`HistoryCell`] without recording history.
+    /// Send signal to context.
+    pub fn invalidate(this: &mut Self) {
+        if !this.changed {
+            this.changed = true;
+        }
+
+        this.tracked.invalidate();
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    fn record(&mut self) {
+        if self.begin_record() {
+            self.push_undo(self.inner.clone());
+        }
+    }
+
+    /// Returns false if current transaction already has its entry
+    fn begin_record(&mut self) -> bool {
+        if self.transaction_depth > 0 {
+            if self.transaction_recorded {
+                return false;
+            }
+
+            self.transaction_recorded = true;
+        }
+
+        true
+    }
+
+    fn push_undo(&mut self, previous: T) {
+        self.undo.push_back(previous);
+        self.redo.clear();
+        self.trim();
+    }
+
+    fn end_transactions(&mut self) {
+        self.transaction_depth = 0;
+        self.transaction_recorded = false;
+    }
+
+    fn trim(&mut self) {
+        if let Some(limit) = self.limit {
+            while self.undo.len() > limit {
+                self.undo.pop_front();
+            }
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> HistoryCell<T> {
+    /// Run closure inside transaction.
+    /// Mutations made by the closure are recorded as one entry.
+    ///
+    /// Nothing is recorded and redo history is kept if the closure leaves value unchanged.
+    pub fn transaction<R>(this: &mut Self, func: impl FnOnce(&mut T) -> R) -> R {
+        let previous = this.inner.clone();
+
+        HistoryCell::begin(this);
+        let output = func(&mut this.inner);
+
+        if this.inner != previous {
+            if this.begin_record() {
+                this.push_undo(previous);
+            }
+
+            HistoryCell::invalidate(this);
+        }
+        HistoryCell::commit(this);
+
+        output
+    }
+}
+
+impl<T> Deref for HistoryCell<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.inner
+    }
+}
+
+impl<T: Clone> DerefMut for HistoryCell<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.record();
+        HistoryCell::invalidate(self);
+
+        &mut self.inner
+    }
+}
+
+impl<T> Unpin for HistoryCell<T> {}
+
+impl<T> State for HistoryCell<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        if this.changed {
+            this.changed = false;
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Clone> From<T> for HistoryCell<T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T: Clone + Default> Default for HistoryCell<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> Drop for HistoryCell<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,45 @@
+//! Calls update hooks of derived component with either `&mut self` or pinned `self`
+
+use std::pin::Pin;
+
+/// Hook taking `&mut self`. Component must be [`Unpin`].
+#[derive(Debug)]
+pub struct ByRef;
+
+/// Hook taking `self: Pin<&mut Self>`
+#[derive(Debug)]
+pub struct ByPin;
+
+/// Hook called with output of updated state
+pub trait StateHook<C: ?Sized, T, Marker> {
+    fn call(self, this: Pin<&mut C>, output: T);
+}
+
+impl<C: ?Sized + Unpin, T, F: FnOnce(&mut C, T)> StateHook<C, T, ByRef> for F {
+    fn call(self, this: Pin<&mut C>, output: T) {
+        self(this.get_mut(), output)
+    }
+}
+
+impl<C: ?Sized, T, F: FnOnce(Pin<&mut C>, T)> StateHook<C, T, ByPin> for F {
+    fn call(self, this: Pin<&mut C>, output: T) {
+        self(this, output)
+    }
+}
+
+/// Hook called after every update of component
+pub trait ComponentHook<C: ?Sized, Marker> {
+    fn call(self, this: Pin<&mut C>);
+}
+
+impl<C: ?Sized + Unpin, F: FnOnce(&mut C)> ComponentHook<C, ByRef> for F {
+    fn call(self, this: Pin<&mut C>) {
+        self(this.get_mut())
+    }
+}
+
+impl<C: ?Sized, F: FnOnce(Pin<&mut C>)> ComponentHook<C, ByPin> for F {
+    fn call(self, this: Pin<&mut C>) {
+        self(this)
+    }
+}
@@ -0,0 +1,222 @@
+//! Timer states driven by [`crate::timer::Timer`] of running executor
+
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    context::{current_time, try_with_current_context, with_current_context, StateContext},
+    State,
+};
+
+/// State which fires once when deadline is reached
+#[derive(Debug)]
+pub struct DeadlineCell {
+    deadline: Option<Instant>,
+    registered: Option<Instant>,
+}
+
+impl DeadlineCell {
+    /// Create new [`DeadlineCell`] firing at `deadline`
+    pub fn new(deadline: Instant) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            deadline: Some(deadline),
+            registered: None,
+        }
+    }
+
+    /// Deadline which is not fired yet
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Returns true if deadline is fired or cancelled
+    pub fn is_elapsed(&self) -> bool {
+        self.deadline.is_none()
+    }
+
+    /// Set new deadline
+    pub fn set(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Cancel deadline
+    pub fn cancel(&mut self) {
+        self.deadline = None;
+    }
+}
+
+impl State for DeadlineCell {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        let deadline = this.deadline?;
+
+        with_current_context(|cx| {
+            if cx.timer().now() >= deadline {
+                this.deadline = None;
+                this.registered = None;
+
+                Some(())
+            } else {
+                if this.registered != Some(deadline) {
+                    this.registered = Some(deadline);
+                    cx.signal_at(deadline);
+                }
+
+                None
+            }
+        })
+    }
+}
+
+impl From<Instant> for DeadlineCell {
+    fn from(deadline: Instant) -> Self {
+        Self::new(deadline)
+    }
+}
+
+impl Drop for DeadlineCell {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+/// State which fires once after duration
+#[derive(Debug)]
+pub struct TimeoutCell {
+    inner: DeadlineCell,
+}
+
+impl TimeoutCell {
+    /// Create new [`TimeoutCell`] firing after `duration`
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            inner: DeadlineCell::new(current_time() + duration),
+        }
+    }
+
+    /// Returns true if timeout is fired or cancelled
+    pub fn is_elapsed(&self) -> bool {
+        self.inner.is_elapsed()
+    }
+
+    /// Restart timeout with new duration
+    pub fn reset(&mut self, duration: Duration) {
+        self.inner.set(current_time() + duration);
+    }
+
+    /// Cancel timeout
+    pub fn cancel(&mut self) {
+        self.inner.cancel();
+    }
+}
+
+impl State for TimeoutCell {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        State::update(Pin::new(&mut this.get_mut().inner))
+    }
+}
+
+impl From<Duration> for TimeoutCell {
+    fn from(duration: Duration) -> Self {
+        Self::new(duration)
+    }
+}
+
+/// State which fires every period.
+///
+/// Yields number of elapsed periods since last update, so missed ticks are coalesced.
+#[derive(Debug)]
+pub struct IntervalCell {
+    period: Duration,
+    next: Instant,
+    registered: Option<Instant>,
+}
+
+impl IntervalCell {
+    /// Create new [`IntervalCell`] firing first after `period`.
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(period: Duration) -> Self {
+        assert!(!period.is_zero(), "Interval period must be non zero");
+
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            period,
+            next: current_time() + period,
+            registered: None,
+        }
+    }
+
+    /// Period of interval
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Restart interval from now
+    pub fn reset(&mut self) {
+        self.next = current_time() + self.period;
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Set new period and restart interval.
+    ///
+    /// Panics if `period` is zero.
+    pub fn set_period(&mut self, period: Duration) {
+        assert!(!period.is_zero(), "Interval period must be non zero");
+
+        self.period = period;
+        self.reset();
+    }
+}
+
+impl State for IntervalCell {
+    type Output = u32;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| {
+            let now = cx.timer().now();
+
+            let ticks = if now >= this.next {
+                let ticks = (now - this.next).as_nanos() / this.period.as_nanos() + 1;
+                this.next += this.period * ticks as u32;
+
+                Some(ticks as u32)
+            } else {
+                None
+            };
+
+            if this.registered != Some(this.next) {
+                this.registered = Some(this.next);
+                cx.signal_at(this.next);
+            }
+
+            ticks
+        })
+    }
+}
+
+impl From<Duration> for IntervalCell {
+    fn from(period: Duration) -> Self {
+        Self::new(period)
+    }
+}
+
+impl Drop for IntervalCell {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
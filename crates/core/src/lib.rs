@@ -3,9 +3,57 @@
 #[doc(hidden)]
 #[path = "exports.rs"]
 pub mod __private;
+pub mod batch;
+pub mod blocking;
 pub mod context;
+pub mod debounce;
+pub mod event;
+pub mod future;
+pub mod history;
+mod hook;
+pub mod interval;
+pub mod map;
+pub mod memo;
+#[cfg(feature = "serde")]
+pub mod persistent;
+#[cfg(feature = "process")]
+pub mod process;
+pub mod query;
+pub mod remote;
+pub mod report;
+pub mod resource;
+pub mod shared;
+pub mod task;
+pub mod timer;
+pub mod vec;
+pub mod watch;
 
-use context::{with_current_context, StateContext};
+pub use batch::BatchStreamCell;
+pub use blocking::BlockingCell;
+pub use context::batch;
+pub use debounce::{DebounceCell, ThrottleCell};
+pub use event::{EventComponent, EventQueue};
+pub use future::FutureCell;
+pub use history::HistoryCell;
+pub use interval::{DeadlineCell, IntervalCell, TimeoutCell};
+pub use map::StateMap;
+pub use memo::MemoCell;
+#[cfg(feature = "serde")]
+pub use persistent::PersistentCell;
+#[cfg(feature = "process")]
+pub use process::ProcessCell;
+pub use query::{QueryCell, QueryClient};
+pub use remote::RemoteState;
+pub use resource::ResourceCell;
+pub use shared::SharedState;
+#[cfg(feature = "sync")]
+pub use shared::SyncSharedState;
+pub use task::TaskCell;
+pub use vec::StateVec;
+pub use watch::FileWatchCell;
+
+use context::{try_with_current_context, with_current_context, StateContext};
+use memo::Tracked;
 use futures_core::Stream;
 
 use std::{
@@ -15,8 +63,11 @@ use std::{
 };
 
 /// Core trait
+///
+/// Component is updated in pinned place, so fields marked with `#[pin]` can hold `!Unpin` states.
+/// Hooks of such component take `self: Pin<&mut Self>` and access fields using `project` method generated by derive.
 pub trait AsyncComponent {
-    fn update_component(&mut self);
+    fn update_component(self: Pin<&mut Self>);
 }
 
 /// State trait
@@ -25,27 +76,31 @@ pub trait AsyncComponent {
 pub trait State {
     type Output;
 
-    fn update(this: &mut Self) -> Option<Self::Output>;
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output>;
 }
 
 /// Track change of value and signal to [`StateContext`].
 /// This struct has no method and implements [`Deref`], [`DerefMut`].
 /// When inner value is mutable dereferenced, it is marked changed and send signal.
+/// Use [`StateCell::set`], [`StateCell::replace`] or [`StateCell::update_with`] to send signal only if value is actually changed.
 /// This will also send signal when the cell is constructed or dropped.
+/// Reads are recorded as dependency of [`MemoCell`].
 #[derive(Debug)]
 pub struct StateCell<T> {
     changed: bool,
     inner: T,
+    tracked: Tracked,
 }
 
 impl<T> StateCell<T> {
     /// Create new [`StateCell`]
     pub fn new(inner: T) -> Self {
-        with_current_context(StateContext::signal);
+        try_with_current_context(StateContext::signal);
 
         Self {
             changed: true,
             inner,
+            tracked: Tracked::new(),
         }
     }
 
@@ -56,7 +111,52 @@ impl<T> StateCell<T> {
             this.changed = true;
         }
 
-        with_current_context(StateContext::signal);
+        this.tracked.invalidate();
+
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+impl<T: PartialEq> StateCell<T> {
+    /// Set inner value.
+    /// Invalidated only if new value is not equal to current one.
+    ///
+    /// Returns true if value is changed.
+    pub fn set(this: &mut Self, value: T) -> bool {
+        if this.inner == value {
+            return false;
+        }
+
+        this.inner = value;
+        StateCell::invalidate(this);
+
+        true
+    }
+
+    /// Replace inner value and returns previous one.
+    /// Invalidated only if new value is not equal to current one.
+    pub fn replace(this: &mut Self, value: T) -> T {
+        if this.inner == value {
+            return value;
+        }
+
+        let old = std::mem::replace(&mut this.inner, value);
+        StateCell::invalidate(this);
+
+        old
+    }
+}
+
+impl<T: Clone + PartialEq> StateCell<T> {
+    /// Mutate inner value using closure.
+    /// Invalidated only if mutated value is not equal to previous one.
+    ///
+    /// Returns true if value is changed.
+    pub fn update_with(this: &mut Self, func: impl FnOnce(&mut T)) -> bool {
+        let mut value = this.inner.clone();
+        func(&mut value);
+
+        StateCell::set(this, value)
     }
 }
 
@@ -64,6 +164,8 @@ impl<T> Deref for StateCell<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
         &self.inner
     }
 }
@@ -76,10 +178,14 @@ impl<T> DerefMut for StateCell<T> {
     }
 }
 
+impl<T> Unpin for StateCell<T> {}
+
 impl<T> State for StateCell<T> {
     type Output = ();
 
-    fn update(this: &mut Self) -> Option<Self::Output> {
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
         if this.changed {
             this.changed = false;
             Some(())
@@ -103,32 +209,105 @@ impl<T: Default> Default for StateCell<T> {
 
 impl<T> Drop for StateCell<T> {
     fn drop(&mut self) {
-        with_current_context(StateContext::signal);
+        try_with_current_context(StateContext::signal);
     }
 }
 
-/// State which polls inner stream
+/// Event yielded by stream states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamEvent<T> {
+    /// Item produced by stream
+    Item(T),
+
+    /// Stream is terminated. Yielded once and the stream is not polled anymore.
+    Ended,
+}
+
+impl<T> StreamEvent<T> {
+    /// Returns item if event is [`StreamEvent::Item`]
+    pub fn item(self) -> Option<T> {
+        match self {
+            StreamEvent::Item(item) => Some(item),
+            StreamEvent::Ended => None,
+        }
+    }
+}
+
+/// State which polls inner stream.
+///
+/// Inner stream is structurally pinned, so `!Unpin` stream can be stored without boxing
+/// if the field is marked with `#[pin]`.
 #[derive(Debug)]
 pub struct StreamCell<T> {
     inner: T,
+    terminated: bool,
 }
 
 impl<T: Stream> StreamCell<T> {
     pub fn new(inner: T) -> Self {
-        with_current_context(StateContext::signal);
-        Self { inner }
+        try_with_current_context(StateContext::signal);
+        Self {
+            inner,
+            terminated: false,
+        }
+    }
+
+    /// Returns true if inner stream is terminated
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Replace inner stream with new one
+    pub fn set(&mut self, inner: T) {
+        self.inner = inner;
+        self.terminated = false;
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Replace inner stream of pinned [`StreamCell`] with new one
+    pub fn set_pinned(self: Pin<&mut Self>, inner: T) {
+        let (mut stream, terminated) = self.project();
+
+        stream.set(inner);
+        *terminated = false;
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut T>, &mut bool) {
+        // SAFETY: inner stream is structurally pinned and never moved out of pinned cell
+        unsafe {
+            let this = self.get_unchecked_mut();
+
+            (Pin::new_unchecked(&mut this.inner), &mut this.terminated)
+        }
     }
 }
 
-impl<T: Stream + Unpin> State for StreamCell<T> {
-    type Output = T::Item;
+impl<T: Stream> State for StreamCell<T> {
+    type Output = StreamEvent<T::Item>;
 
-    fn update(this: &mut Self) -> Option<Self::Output> {
-        with_current_context(|cx| {
-            match Pin::new(&mut this.inner).poll_next(&mut cx.task_context()) {
-                Poll::Ready(Some(output)) => Some(output),
-                _ => None,
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let (stream, terminated) = this.project();
+
+        if *terminated {
+            return None;
+        }
+
+        with_current_context(|cx| match stream.poll_next(&mut cx.task_context()) {
+            Poll::Ready(Some(output)) => {
+                // Stream does not wake until it is polled to pending
+                cx.signal();
+                Some(StreamEvent::Item(output))
             }
+
+            Poll::Ready(None) => {
+                *terminated = true;
+                Some(StreamEvent::Ended)
+            }
+
+            Poll::Pending => None,
         })
     }
 }
@@ -147,6 +326,6 @@ impl<T: Stream + Default> Default for StreamCell<T> {
 
 impl<T> Drop for StreamCell<T> {
     fn drop(&mut self) {
-        with_current_context(StateContext::signal);
+        try_with_current_context(StateContext::signal);
     }
 }
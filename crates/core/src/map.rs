@@ -0,0 +1,190 @@
+//! Keyed map state reporting structural changes
+
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash},
+    ops::Deref,
+    pin::Pin,
+};
+
+use crate::{
+    context::{try_with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+/// Structural change of [`StateMap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapChange<K> {
+    /// New entry is inserted
+    Inserted(K),
+
+    /// Entry is removed
+    Removed(K),
+
+    /// Value of entry is replaced or mutated
+    Updated(K),
+
+    /// Every entry is removed
+    Cleared,
+}
+
+/// Map state which yields every structural change made since last update.
+///
+/// Initial entries are reported as [`MapChange::Inserted`].
+#[derive(Debug)]
+pub struct StateMap<K, V, S = RandomState> {
+    inner: HashMap<K, V, S>,
+    changes: Vec<MapChange<K>>,
+    tracked: Tracked,
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> StateMap<K, V, S> {
+    /// Create new [`StateMap`]
+    pub fn new(inner: HashMap<K, V, S>) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            changes: inner.keys().cloned().map(MapChange::Inserted).collect(),
+            inner,
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Insert entry and returns previous value
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old = self.inner.insert(key.clone(), value);
+
+        self.changed(match old {
+            Some(_) => MapChange::Updated(key),
+            None => MapChange::Inserted(key),
+        });
+
+        old
+    }
+
+    /// Remove entry and returns its value
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (key, value) = self.inner.remove_entry(key)?;
+
+        self.changed(MapChange::Removed(key));
+
+        Some(value)
+    }
+
+    /// Mutably borrow value of entry
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let changed_key = self.inner.get_key_value(key)?.0.clone();
+
+        self.changed(MapChange::Updated(changed_key));
+
+        self.inner.get_mut(key)
+    }
+
+    /// Remove every entries
+    pub fn clear(&mut self) {
+        self.inner.clear();
+
+        self.changed(MapChange::Cleared);
+    }
+
+    /// Retain only entries matching predicate
+    pub fn retain(&mut self, mut func: impl FnMut(&K, &mut V) -> bool) {
+        let mut removed = Vec::new();
+
+        self.inner.retain(|key, value| {
+            let retain = func(key, value);
+            if !retain {
+                removed.push(key.clone());
+            }
+
+            retain
+        });
+
+        for key in removed {
+            self.changed(MapChange::Removed(key));
+        }
+    }
+
+    /// Take inner [`HashMap`]
+    pub fn into_inner(mut self) -> HashMap<K, V, S>
+    where
+        S: Default,
+    {
+        std::mem::take(&mut self.inner)
+    }
+
+    fn changed(&mut self, change: MapChange<K>) {
+        self.changes.push(change);
+        self.tracked.invalidate();
+
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+impl<K, V, S> Deref for StateMap<K, V, S> {
+    type Target = HashMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.inner
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> Extend<(K, V)> for StateMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V, S> Unpin for StateMap<K, V, S> {}
+
+impl<K, V, S> State for StateMap<K, V, S> {
+    type Output = Vec<MapChange<K>>;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        if this.changes.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut this.changes))
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> From<HashMap<K, V, S>> for StateMap<K, V, S> {
+    fn from(inner: HashMap<K, V, S>) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> FromIterator<(K, V)> for StateMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher + Default> Default for StateMap<K, V, S> {
+    fn default() -> Self {
+        Self::new(HashMap::default())
+    }
+}
+
+impl<K, V, S> Drop for StateMap<K, V, S> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
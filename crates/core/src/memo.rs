@@ -0,0 +1,251 @@
+//! Derived state with automatic dependency tracking
+
+use std::{
+    cell::RefCell,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+};
+
+use crate::{
+    context::{try_with_current_context, StateContext},
+    State,
+};
+
+thread_local! {
+    static TRACKING: RefCell<Option<Vec<Dependency>>> = const { RefCell::new(None) };
+}
+
+/// Number of active tracking scopes on every thread.
+/// Reads skip thread local access while nothing is tracked.
+static TRACKING_SCOPES: AtomicUsize = AtomicUsize::new(0);
+
+/// Run closure and collect every state read while running it.
+///
+/// Collected [`Dependencies`] can be used to check if any of them was changed afterward.
+pub fn track<R>(func: impl FnOnce() -> R) -> (R, Dependencies) {
+    let scope = TrackScope::enter();
+
+    let output = func();
+    let list = TRACKING.with(|tracking| tracking.borrow_mut().take());
+
+    drop(scope);
+
+    (output, Dependencies(list.unwrap_or_default()))
+}
+
+/// Restore previous tracking scope on drop
+#[derive(Debug)]
+struct TrackScope(Option<Vec<Dependency>>);
+
+impl TrackScope {
+    fn enter() -> Self {
+        TRACKING_SCOPES.fetch_add(1, Ordering::Relaxed);
+
+        Self(TRACKING.with(|tracking| tracking.replace(Some(Vec::new()))))
+    }
+}
+
+impl Drop for TrackScope {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+
+        TRACKING.with(|tracking| *tracking.borrow_mut() = previous);
+
+        TRACKING_SCOPES.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Version counter of a state which can be depended on.
+/// Counter is allocated lazily when the state is read while tracking.
+#[derive(Debug)]
+pub(crate) struct Tracked(OnceLock<Arc<AtomicUsize>>);
+
+impl Tracked {
+    pub const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    /// Record read to current tracking scope
+    pub fn read(&self) {
+        if TRACKING_SCOPES.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+
+        TRACKING.with(|tracking| {
+            if let Some(ref mut list) = *tracking.borrow_mut() {
+                let version = self.0.get_or_init(Default::default).clone();
+                let seen = version.load(Ordering::Acquire);
+
+                list.push(Dependency { version, seen });
+            }
+        })
+    }
+
+    /// Mark every dependent changed
+    pub fn invalidate(&self) {
+        if let Some(version) = self.0.get() {
+            version.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Dependency {
+    version: Arc<AtomicUsize>,
+    seen: usize,
+}
+
+/// List of states read inside [`track`]
+#[derive(Debug, Default)]
+pub struct Dependencies(Vec<Dependency>);
+
+impl Dependencies {
+    /// Returns true if any of dependencies is changed since tracked
+    pub fn is_changed(&self) -> bool {
+        self.0
+            .iter()
+            .any(|dependency| dependency.version.load(Ordering::Acquire) != dependency.seen)
+    }
+
+    /// Number of recorded reads
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if nothing was read
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// State holding value derived from other states.
+///
+/// Value is recomputed lazily only if any state read during last computation is changed.
+/// Signal is sent only if the recomputed value is not equal to previous one.
+///
+/// Use `#[memo(Self::compute)]` attribute with derive macro to check it on every component update.
+/// Value is recomputed only if stale, and the change is reported in the same update without extra signal.
+#[derive(Debug)]
+pub struct MemoCell<T> {
+    value: Option<T>,
+    dependencies: Option<Dependencies>,
+    changed: bool,
+    tracked: Tracked,
+}
+
+impl<T> MemoCell<T> {
+    /// Create new [`MemoCell`] which is not computed yet
+    pub fn new() -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            value: None,
+            dependencies: None,
+            changed: false,
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Returns computed value. [`None`] if not computed yet.
+    pub fn get(&self) -> Option<&T> {
+        self.tracked.read();
+
+        self.value.as_ref()
+    }
+
+    /// Returns true if value needs to be recomputed
+    pub fn is_stale(&self) -> bool {
+        self.dependencies
+            .as_ref()
+            .is_none_or(Dependencies::is_changed)
+    }
+
+    /// Force recompute on next update
+    pub fn invalidate(&mut self) {
+        self.dependencies = None;
+
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+impl<T: PartialEq> MemoCell<T> {
+    /// Store value computed with given [`Dependencies`].
+    ///
+    /// Returns true if value is changed.
+    pub fn set_computed(&mut self, value: T, dependencies: Dependencies) -> bool {
+        if !store_computed(self, value, dependencies) {
+            return false;
+        }
+
+        try_with_current_context(StateContext::signal);
+
+        true
+    }
+
+    /// Recompute value using closure if stale.
+    ///
+    /// Returns true if value is changed.
+    pub fn refresh(&mut self, func: impl FnOnce() -> T) -> bool {
+        if !self.is_stale() {
+            return false;
+        }
+
+        let (value, dependencies) = track(func);
+
+        self.set_computed(value, dependencies)
+    }
+}
+
+/// Store value computed with given [`Dependencies`] without sending signal.
+///
+/// Used by derive, which polls the memo after refreshing it in the same update.
+#[doc(hidden)]
+pub fn store_computed<T: PartialEq>(
+    memo: &mut MemoCell<T>,
+    value: T,
+    dependencies: Dependencies,
+) -> bool {
+    memo.dependencies = Some(dependencies);
+
+    if memo.value.as_ref() == Some(&value) {
+        return false;
+    }
+
+    memo.value = Some(value);
+    memo.changed = true;
+    memo.tracked.invalidate();
+
+    true
+}
+
+impl<T> Unpin for MemoCell<T> {}
+
+impl<T> State for MemoCell<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        if this.changed {
+            this.changed = false;
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Default for MemoCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MemoCell<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,328 @@
+//! State persisted to file
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use atomic_waker::AtomicWaker;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    blocking,
+    context::{current_time, try_with_current_context, with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+/// Error occurred while loading or saving [`PersistentCell`]
+#[derive(Debug)]
+pub enum PersistError {
+    /// Failed to read or write file
+    Io(io::Error),
+
+    /// Failed to serialize or deserialize value
+    Format(serde_json::Error),
+}
+
+impl Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistError::Io(err) => write!(f, "io error: {err}"),
+            PersistError::Format(err) => write!(f, "format error: {err}"),
+        }
+    }
+}
+
+impl Error for PersistError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PersistError::Io(err) => Some(err),
+            PersistError::Format(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for PersistError {
+    fn from(err: io::Error) -> Self {
+        PersistError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(err: serde_json::Error) -> Self {
+        PersistError::Format(err)
+    }
+}
+
+fn load<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, PersistError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(Some(serde_json::from_reader(BufReader::new(file))?))
+}
+
+/// Write to temporary file first and rename it, so the file is never left partially written
+fn write(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        writer.write_all(contents)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        fs::remove_file(&temp_path).ok();
+    }
+
+    result
+}
+
+/// Writes file of [`PersistentCell`] on blocking worker thread
+#[derive(Debug)]
+struct Writer {
+    /// Generation of last written contents.
+    /// Locked while writing, so older contents never overwrite newer one.
+    written: Mutex<usize>,
+
+    /// Result of latest finished write
+    result: Mutex<Option<(usize, io::Result<()>)>>,
+    waker: AtomicWaker,
+}
+
+impl Writer {
+    fn write(&self, generation: usize, path: &Path, contents: &[u8]) -> Option<io::Result<()>> {
+        let mut written = self.written.lock().unwrap();
+        if *written >= generation {
+            return None;
+        }
+        *written = generation;
+
+        Some(write(path, contents))
+    }
+
+    fn finish(&self, generation: usize, result: io::Result<()>) {
+        let mut last = self.result.lock().unwrap();
+        if last.as_ref().is_none_or(|(last, _)| *last < generation) {
+            *last = Some((generation, result));
+        }
+        drop(last);
+
+        self.waker.wake();
+    }
+}
+
+/// [`crate::StateCell`] which loads initial value from file and writes every change back to it.
+///
+/// Changes are saved after value stays unchanged for `delay`.
+/// Value is serialized on update and written on blocking worker thread, except pending change on drop which is written immediately.
+/// Load and save errors are kept as state and reported like value changes.
+#[derive(Debug)]
+pub struct PersistentCell<T: Serialize> {
+    changed: bool,
+    inner: T,
+    tracked: Tracked,
+
+    path: PathBuf,
+    delay: Duration,
+    deadline: Option<Instant>,
+    registered: Option<Instant>,
+    error: Option<PersistError>,
+
+    writer: Arc<Writer>,
+    generation: usize,
+    saved_generation: usize,
+}
+
+impl<T: Serialize + DeserializeOwned> PersistentCell<T> {
+    /// Create new [`PersistentCell`] loading value from `path`.
+    /// Value from `default` is used if the file does not exist or cannot be loaded.
+    pub fn new(path: impl Into<PathBuf>, delay: Duration, default: impl FnOnce() -> T) -> Self {
+        let path = path.into();
+
+        let (inner, error) = match load(&path) {
+            Ok(Some(inner)) => (inner, None),
+            Ok(None) => (default(), None),
+            Err(err) => (default(), Some(err)),
+        };
+
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            changed: true,
+            inner,
+            tracked: Tracked::new(),
+
+            path,
+            delay,
+            deadline: None,
+            registered: None,
+            error,
+
+            writer: Arc::new(Writer {
+                written: Mutex::new(0),
+                result: Mutex::new(None),
+                waker: AtomicWaker::new(),
+            }),
+            generation: 0,
+            saved_generation: 0,
+        }
+    }
+}
+
+impl<T: Serialize> PersistentCell<T> {
+    /// Path of the file
+    pub fn path(this: &Self) -> &Path {
+        &this.path
+    }
+
+    /// Error of last load or save
+    pub fn error(this: &Self) -> Option<&PersistError> {
+        this.error.as_ref()
+    }
+
+    /// Returns true if a change is waiting to be saved or being written
+    pub fn is_dirty(this: &Self) -> bool {
+        this.deadline.is_some() || this.saved_generation < this.generation
+    }
+
+    /// Invalidate this [`PersistentCell`] and postpone save.
+    /// Send signal to context.
+    pub fn invalidate(this: &mut Self) {
+        this.deadline = Some(current_time() + this.delay);
+        try_with_current_context(StateContext::signal);
+
+        this.changed = true;
+        this.tracked.invalidate();
+    }
+
+    /// Save pending change on next update without waiting
+    pub fn flush(this: &mut Self) {
+        if this.deadline.is_some() {
+            this.deadline = Some(current_time());
+            try_with_current_context(StateContext::signal);
+        }
+    }
+
+    fn save(this: &mut Self) {
+        this.deadline = None;
+        this.registered = None;
+
+        let contents = match serde_json::to_vec_pretty(&this.inner) {
+            Ok(contents) => contents,
+            Err(err) => {
+                PersistentCell::set_error(this, Some(err.into()));
+                return;
+            }
+        };
+
+        this.generation += 1;
+
+        let generation = this.generation;
+        let writer = this.writer.clone();
+        let path = this.path.clone();
+        blocking::submit(Box::new(move || {
+            if let Some(result) = writer.write(generation, &path, &contents) {
+                writer.finish(generation, result);
+            }
+        }));
+    }
+
+    fn set_error(this: &mut Self, error: Option<PersistError>) {
+        if error.is_some() || this.error.is_some() {
+            this.changed = true;
+        }
+        this.error = error;
+    }
+}
+
+impl<T: Serialize> Deref for PersistentCell<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.inner
+    }
+}
+
+impl<T: Serialize> DerefMut for PersistentCell<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        PersistentCell::invalidate(self);
+
+        &mut self.inner
+    }
+}
+
+impl<T: Serialize> Unpin for PersistentCell<T> {}
+
+impl<T: Serialize> State for PersistentCell<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| this.writer.waker.register(cx.task_context().waker()));
+
+        let finished = this.writer.result.lock().unwrap().take();
+        if let Some((generation, result)) = finished {
+            this.saved_generation = generation;
+            PersistentCell::set_error(this, result.err().map(PersistError::Io));
+        }
+
+        if let Some(deadline) = this.deadline {
+            let elapsed = with_current_context(|cx| {
+                if cx.timer().now() >= deadline {
+                    true
+                } else {
+                    if this.registered != Some(deadline) {
+                        this.registered = Some(deadline);
+                        cx.signal_at(deadline);
+                    }
+
+                    false
+                }
+            });
+
+            if elapsed {
+                PersistentCell::save(this);
+            }
+        }
+
+        if this.changed {
+            this.changed = false;
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Serialize> Drop for PersistentCell<T> {
+    fn drop(&mut self) {
+        if self.deadline.is_some() {
+            if let Ok(contents) = serde_json::to_vec_pretty(&self.inner) {
+                self.writer
+                    .write(self.generation + 1, &self.path, &contents);
+            }
+        }
+
+        try_with_current_context(StateContext::signal);
+    }
+}
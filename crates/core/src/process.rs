@@ -0,0 +1,278 @@
+//! State which runs child process and reports its output
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read},
+    pin::Pin,
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use atomic_waker::AtomicWaker;
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    State,
+};
+
+/// Output of child process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessEvent {
+    /// Line written to stdout
+    Stdout(String),
+
+    /// Line written to stderr
+    Stderr(String),
+
+    /// Process exited
+    Exited(ExitStatus),
+}
+
+#[derive(Debug)]
+struct Shared {
+    child: Mutex<Child>,
+    readers: AtomicUsize,
+    events: Mutex<VecDeque<ProcessEvent>>,
+    waker: AtomicWaker,
+}
+
+impl Shared {
+    fn push(&self, event: ProcessEvent) {
+        self.events.lock().unwrap().push_back(event);
+
+        self.waker.wake();
+    }
+
+    fn read_lines(&self, reader: impl Read, map: fn(String) -> ProcessEvent) {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+
+        loop {
+            buf.clear();
+
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+
+                Ok(_) => {
+                    if buf.ends_with(b"\n") {
+                        buf.pop();
+
+                        if buf.ends_with(b"\r") {
+                            buf.pop();
+                        }
+                    }
+
+                    self.push(map(String::from_utf8_lossy(&buf).into_owned()));
+                }
+            }
+        }
+
+        self.reader_finished();
+    }
+
+    /// Last reader waits for exit after every output is read
+    fn reader_finished(&self) {
+        if self.readers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.wait_exit();
+        }
+    }
+
+    fn wait_exit(&self) {
+        // Wait without holding the lock, so the process can be killed meanwhile
+        let exited = wait_exited(&self.child);
+
+        let status = match exited {
+            Ok(()) => self.child.lock().unwrap().wait(),
+            Err(err) => Err(err),
+        };
+
+        if let Ok(status) = status {
+            self.push(ProcessEvent::Exited(status));
+        }
+    }
+}
+
+/// Block until the process exits.
+/// The process is not reaped, so its id stays valid until [`Child::wait`].
+#[cfg(unix)]
+fn wait_exited(child: &Mutex<Child>) -> io::Result<()> {
+    let id = child.lock().unwrap().id();
+
+    loop {
+        // SAFETY: all zero siginfo_t is valid
+        let mut info = unsafe { std::mem::zeroed::<libc::siginfo_t>() };
+
+        // SAFETY: `info` is valid for write
+        let res = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                id as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOWAIT,
+            )
+        };
+
+        if res == 0 {
+            return Ok(());
+        }
+
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+/// Block until the process exits
+#[cfg(windows)]
+fn wait_exited(child: &Mutex<Child>) -> io::Result<()> {
+    use std::os::windows::io::AsRawHandle;
+
+    use windows_sys::Win32::{
+        Foundation::WAIT_FAILED,
+        System::Threading::{WaitForSingleObject, INFINITE},
+    };
+
+    let handle = child.lock().unwrap().as_raw_handle();
+
+    // SAFETY: handle is owned by `child` which outlives the call
+    match unsafe { WaitForSingleObject(handle, INFINITE) } {
+        WAIT_FAILED => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    }
+}
+
+/// Returns immediately, so [`Child::wait`] holds the lock and killing the process blocks until it exits
+#[cfg(not(any(unix, windows)))]
+fn wait_exited(_: &Mutex<Child>) -> io::Result<()> {
+    Ok(())
+}
+
+fn spawn_reader(
+    shared: &Arc<Shared>,
+    reader: Option<impl Read + Send + 'static>,
+    map: fn(String) -> ProcessEvent,
+) -> io::Result<()> {
+    let shared = shared.clone();
+
+    thread::Builder::new()
+        .name("async-component-process".into())
+        .spawn(move || match reader {
+            Some(reader) => shared.read_lines(reader, map),
+            None => shared.read_lines(io::empty(), map),
+        })?;
+
+    Ok(())
+}
+
+/// State which runs child process and yields [`ProcessEvent`] for every output line and exit.
+///
+/// Yields one event per update. The process is killed when the cell is dropped.
+#[derive(Debug)]
+pub struct ProcessCell {
+    id: u32,
+    exited: bool,
+    shared: Arc<Shared>,
+}
+
+impl ProcessCell {
+    /// Spawn `command` with piped stdout and stderr
+    pub fn spawn(command: &mut Command) -> io::Result<Self> {
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        let shared = Arc::new(Shared {
+            child: Mutex::new(child),
+            // Stdout and stderr readers. Count of reader failed to spawn is released below.
+            readers: AtomicUsize::new(2),
+            events: Mutex::new(VecDeque::new()),
+            waker: AtomicWaker::new(),
+        });
+
+        let id = shared.child.lock().unwrap().id();
+
+        if let Err(err) = spawn_reader(&shared, stdout, ProcessEvent::Stdout) {
+            let mut child = shared.child.lock().unwrap();
+            child.kill().ok();
+            child.wait().ok();
+
+            return Err(err);
+        }
+
+        if let Err(err) = spawn_reader(&shared, stderr, ProcessEvent::Stderr) {
+            shared.child.lock().unwrap().kill().ok();
+
+            // Release count of reader which is not spawned, so the stdout reader reaps the process
+            shared.reader_finished();
+
+            return Err(err);
+        }
+
+        try_with_current_context(StateContext::signal);
+
+        Ok(Self {
+            id,
+            exited: false,
+            shared,
+        })
+    }
+
+    /// OS assigned process identifier
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns true if exit of the process is reported
+    pub fn is_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Kill the process
+    pub fn kill(&self) -> io::Result<()> {
+        self.shared.child.lock().unwrap().kill()
+    }
+}
+
+impl State for ProcessCell {
+    type Output = ProcessEvent;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| {
+            this.shared.waker.register(cx.task_context().waker());
+
+            let mut events = this.shared.events.lock().unwrap();
+
+            let event = events.pop_front();
+            if !events.is_empty() {
+                cx.signal();
+            }
+
+            if let Some(ProcessEvent::Exited(_)) = event {
+                this.exited = true;
+            }
+
+            event
+        })
+    }
+}
+
+impl Drop for ProcessCell {
+    fn drop(&mut self) {
+        if !self.exited {
+            self.kill().ok();
+        }
+
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,348 @@
+//! Keyed query cache shared across components
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt::{self, Debug},
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    State,
+};
+
+type Fetch<T, E> = Pin<Box<dyn Future<Output = Result<T, E>>>>;
+
+type Loader<K, T, E> = dyn Fn(&K) -> Fetch<T, E>;
+
+/// Wakes every subscriber of an entry
+#[derive(Debug, Default)]
+struct Subscribers {
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl Subscribers {
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap();
+
+        if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    fn wake_all(&self) {
+        for waker in std::mem::take(&mut *self.wakers.lock().unwrap()) {
+            waker.wake();
+        }
+    }
+}
+
+impl Wake for Subscribers {
+    fn wake(self: Arc<Self>) {
+        self.wake_all();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_all();
+    }
+}
+
+struct Entry<T, E> {
+    data: RefCell<Option<Rc<T>>>,
+    error: RefCell<Option<Rc<E>>>,
+    fetch: RefCell<Option<Fetch<T, E>>>,
+
+    stale: Cell<bool>,
+    version: Cell<usize>,
+
+    subscribers: Arc<Subscribers>,
+    subscriber_count: Cell<usize>,
+}
+
+impl<T, E> Entry<T, E> {
+    fn new() -> Self {
+        Self {
+            data: RefCell::new(None),
+            error: RefCell::new(None),
+            fetch: RefCell::new(None),
+
+            stale: Cell::new(true),
+            version: Cell::new(0),
+
+            subscribers: Default::default(),
+            subscriber_count: Cell::new(0),
+        }
+    }
+
+    fn subscribe(self: &Rc<Self>) -> Rc<Self> {
+        self.subscriber_count.set(self.subscriber_count.get() + 1);
+
+        self.clone()
+    }
+
+    /// Cancel in-flight fetch if no subscriber is left.
+    /// Entry is kept stale so next subscriber fetches again.
+    fn unsubscribe(&self) {
+        let count = self.subscriber_count.get() - 1;
+        self.subscriber_count.set(count);
+
+        if count == 0 {
+            let fetch = self.fetch.borrow_mut().take();
+
+            if fetch.is_some() {
+                self.stale.set(true);
+            }
+        }
+    }
+
+    fn changed(&self) {
+        self.version.set(self.version.get().wrapping_add(1));
+        self.subscribers.wake_all();
+    }
+
+    fn poll_fetch(&self) {
+        let mut fetch = self.fetch.borrow_mut();
+
+        let result = match *fetch {
+            Some(ref mut inner) => {
+                let waker = Waker::from(self.subscribers.clone());
+
+                match inner.as_mut().poll(&mut Context::from_waker(&waker)) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return,
+                }
+            }
+
+            None => return,
+        };
+        *fetch = None;
+        drop(fetch);
+
+        match result {
+            Ok(data) => {
+                *self.data.borrow_mut() = Some(Rc::new(data));
+                *self.error.borrow_mut() = None;
+            }
+
+            Err(err) => {
+                *self.error.borrow_mut() = Some(Rc::new(err));
+            }
+        }
+
+        self.changed();
+    }
+}
+
+struct ClientInner<K, T, E> {
+    loader: Box<Loader<K, T, E>>,
+    entries: RefCell<HashMap<K, Rc<Entry<T, E>>>>,
+}
+
+/// Keyed query cache.
+///
+/// Fetches are deduplicated per key and results are shared by every [`QueryCell`] subscribing the key.
+/// Invalidated data is kept while revalidating.
+pub struct QueryClient<K, T, E> {
+    inner: Rc<ClientInner<K, T, E>>,
+}
+
+impl<K: Eq + Hash + Clone, T, E> QueryClient<K, T, E> {
+    /// Create new [`QueryClient`] fetching each key using loader
+    pub fn new<F>(loader: impl Fn(&K) -> F + 'static) -> Self
+    where
+        F: Future<Output = Result<T, E>> + 'static,
+    {
+        Self {
+            inner: Rc::new(ClientInner {
+                loader: Box::new(move |key| Box::pin(loader(key))),
+                entries: RefCell::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Cached data of key
+    pub fn get(&self, key: &K) -> Option<Rc<T>> {
+        self.inner.entries.borrow().get(key)?.data.borrow().clone()
+    }
+
+    /// Set cached data of key and notify subscribers
+    pub fn set_data(&self, key: K, data: T) {
+        let entry = self.entry(key);
+
+        *entry.data.borrow_mut() = Some(Rc::new(data));
+        *entry.error.borrow_mut() = None;
+        entry.stale.set(false);
+
+        entry.changed();
+    }
+
+    /// Mark data of key stale.
+    /// Subscribers refetch it while keeping stale data.
+    pub fn invalidate(&self, key: &K) {
+        if let Some(entry) = self.inner.entries.borrow().get(key) {
+            entry.stale.set(true);
+            entry.subscribers.wake_all();
+        }
+    }
+
+    /// Mark every cached data stale
+    pub fn invalidate_all(&self) {
+        for entry in self.inner.entries.borrow().values() {
+            entry.stale.set(true);
+            entry.subscribers.wake_all();
+        }
+    }
+
+    /// Remove cached entry of key.
+    /// Current subscribers keep the removed entry until their key is changed.
+    pub fn remove(&self, key: &K) {
+        self.inner.entries.borrow_mut().remove(key);
+    }
+
+    fn entry(&self, key: K) -> Rc<Entry<T, E>> {
+        self.inner
+            .entries
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| Rc::new(Entry::new()))
+            .clone()
+    }
+
+    fn start_fetch(&self, key: &K, entry: &Entry<T, E>) {
+        if entry.stale.get() && entry.fetch.borrow().is_none() {
+            entry.stale.set(false);
+            *entry.fetch.borrow_mut() = Some((self.inner.loader)(key));
+        }
+    }
+}
+
+impl<K, T, E> Clone for QueryClient<K, T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Debug, T, E> Debug for QueryClient<K, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryClient")
+            .field("keys", &self.inner.entries.borrow().keys())
+            .finish_non_exhaustive()
+    }
+}
+
+/// State subscribing key of [`QueryClient`].
+///
+/// Starts fetch if the key is not cached or stale, and reports every time data or error of the key is changed.
+/// In-flight fetch of the key is dropped when its last subscriber is dropped.
+pub struct QueryCell<K, T, E> {
+    client: QueryClient<K, T, E>,
+    key: K,
+    entry: Rc<Entry<T, E>>,
+    seen: Option<usize>,
+}
+
+impl<K: Eq + Hash + Clone, T, E> QueryCell<K, T, E> {
+    /// Create new [`QueryCell`] subscribing `key`
+    pub fn new(client: &QueryClient<K, T, E>, key: K) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            client: client.clone(),
+            entry: client.entry(key.clone()).subscribe(),
+            key,
+            seen: None,
+        }
+    }
+
+    /// Subscribed key
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Subscribe new key
+    pub fn set_key(&mut self, key: K) {
+        let entry = self.client.entry(key.clone()).subscribe();
+        std::mem::replace(&mut self.entry, entry).unsubscribe();
+        self.key = key;
+        self.seen = None;
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Cached data of key
+    pub fn data(&self) -> Option<Rc<T>> {
+        self.entry.data.borrow().clone()
+    }
+
+    /// Error of last fetch
+    pub fn error(&self) -> Option<Rc<E>> {
+        self.entry.error.borrow().clone()
+    }
+
+    /// Returns true if fetch of key is in progress
+    pub fn is_fetching(&self) -> bool {
+        self.entry.fetch.borrow().is_some()
+    }
+
+    /// Returns true if data of key is stale
+    pub fn is_stale(&self) -> bool {
+        self.entry.stale.get()
+    }
+
+    /// Mark data of key stale and refetch it
+    pub fn invalidate(&self) {
+        self.client.invalidate(&self.key);
+    }
+}
+
+impl<K, T, E> Unpin for QueryCell<K, T, E> {}
+
+impl<K: Eq + Hash + Clone, T, E> State for QueryCell<K, T, E> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| {
+            this.entry
+                .subscribers
+                .register(cx.task_context().waker())
+        });
+
+        this.client.start_fetch(&this.key, &this.entry);
+        this.entry.poll_fetch();
+
+        let version = this.entry.version.get();
+        if this.seen != Some(version) {
+            this.seen = Some(version);
+
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<K: Debug, T, E> Debug for QueryCell<K, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryCell")
+            .field("key", &self.key)
+            .field("seen", &self.seen)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, T, E> Drop for QueryCell<K, T, E> {
+    fn drop(&mut self) {
+        self.entry.unsubscribe();
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,201 @@
+//! State which can be modified from other threads
+
+use std::{
+    fmt::{self, Debug},
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+};
+
+use atomic_waker::AtomicWaker;
+
+use crate::{
+    context::{defer_batched, try_with_current_context, with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+type Modification<T> = Box<dyn FnOnce(&mut T) + Send>;
+
+struct Remote<T> {
+    modifications: Mutex<Vec<Modification<T>>>,
+    waker: AtomicWaker,
+}
+
+impl<T> Remote<T> {
+    fn push(&self, modification: Modification<T>) {
+        self.modifications.lock().unwrap().push(modification);
+
+        self.waker.wake();
+    }
+}
+
+impl<T> Debug for Remote<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Remote")
+            .field("waker", &self.waker)
+            .finish_non_exhaustive()
+    }
+}
+
+/// [`crate::StateCell`] which can be modified from other threads using [`RemoteHandle`].
+///
+/// Remote modifications are applied and reported on next update.
+#[derive(Debug)]
+pub struct RemoteState<T> {
+    changed: bool,
+    inner: T,
+    remote: Arc<Remote<T>>,
+    tracked: Tracked,
+}
+
+impl<T> RemoteState<T> {
+    /// Create new [`RemoteState`]
+    pub fn new(inner: T) -> Self {
+        let remote = Arc::new(Remote {
+            modifications: Mutex::new(Vec::new()),
+            waker: AtomicWaker::new(),
+        });
+
+        try_with_current_context(|cx| {
+            remote.waker.register(cx.task_context().waker());
+            cx.signal();
+        });
+
+        Self {
+            changed: true,
+            inner,
+            remote,
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Create new [`RemoteHandle`] which can be sent to other threads
+    pub fn handle(this: &Self) -> RemoteHandle<T> {
+        RemoteHandle {
+            remote: Arc::downgrade(&this.remote),
+        }
+    }
+
+    /// Invalidate this [`RemoteState`].
+    /// Send signal to context.
+    pub fn invalidate(this: &mut Self) {
+        if !this.changed {
+            this.changed = true;
+        }
+
+        this.tracked.invalidate();
+
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+impl<T> Deref for RemoteState<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for RemoteState<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        RemoteState::invalidate(self);
+
+        &mut self.inner
+    }
+}
+
+impl<T> Unpin for RemoteState<T> {}
+
+impl<T> State for RemoteState<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| this.remote.waker.register(cx.task_context().waker()));
+
+        let modifications = std::mem::take(&mut *this.remote.modifications.lock().unwrap());
+        if !modifications.is_empty() {
+            for modification in modifications {
+                modification(&mut this.inner);
+            }
+
+            this.changed = true;
+            this.tracked.invalidate();
+        }
+
+        if this.changed {
+            this.changed = false;
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> From<T> for RemoteState<T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T: Default> Default for RemoteState<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> Drop for RemoteState<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+/// Handle for modifying [`RemoteState`] from any thread.
+///
+/// Modifications are queued and applied on next update of the state.
+#[derive(Debug)]
+pub struct RemoteHandle<T> {
+    remote: Weak<Remote<T>>,
+}
+
+impl<T: Send + 'static> RemoteHandle<T> {
+    /// Set value of the state.
+    ///
+    /// Returns false if the state is dropped.
+    pub fn set(&self, value: T) -> bool {
+        self.modify(move |inner| *inner = value)
+    }
+
+    /// Modify value of the state using closure.
+    /// Queued when the outermost [`crate::context::batch`] of current thread ends if called in it.
+    ///
+    /// Returns false if the state is dropped.
+    pub fn modify(&self, func: impl FnOnce(&mut T) + Send + 'static) -> bool {
+        match self.remote.upgrade() {
+            Some(remote) => {
+                defer_batched(move || remote.push(Box::new(func)));
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    /// Returns true if the state is dropped
+    pub fn is_closed(&self) -> bool {
+        self.remote.strong_count() == 0
+    }
+}
+
+impl<T> Clone for RemoteHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            remote: self.remote.clone(),
+        }
+    }
+}
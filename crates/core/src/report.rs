@@ -0,0 +1,159 @@
+//! Report of changes made by component update
+
+use std::cell::RefCell;
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Default)]
+struct Recorder {
+    path: Vec<&'static str>,
+    changed: ChangedPaths,
+}
+
+/// Paths of changed states stored back to back, so recording a change does not allocate per path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ChangedPaths {
+    segments: Vec<&'static str>,
+    ends: Vec<usize>,
+}
+
+/// Restores previous recorder so nested streams report separately
+#[derive(Debug)]
+struct RecordScope {
+    previous: Option<Recorder>,
+}
+
+impl Drop for RecordScope {
+    fn drop(&mut self) {
+        RECORDER.with(|recorder| *recorder.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Record paths of states changed while calling `func`
+fn record_paths(func: impl FnOnce()) -> ChangedPaths {
+    let _scope = RecordScope {
+        previous: RECORDER.with(|recorder| recorder.replace(Some(Recorder::default()))),
+    };
+
+    func();
+
+    RECORDER.with(|recorder| {
+        recorder
+            .borrow_mut()
+            .as_mut()
+            .map(|recorder| std::mem::take(&mut recorder.changed))
+            .unwrap_or_default()
+    })
+}
+
+/// Record update report of changes made while calling `func`
+pub(crate) fn record(signals: usize, func: impl FnOnce()) -> UpdateReport {
+    UpdateReport {
+        signals,
+        changed: record_paths(func),
+    }
+}
+
+/// Record state `name` of current component path as changed
+pub fn record_changed(name: &'static str) {
+    RECORDER.with(|recorder| {
+        if let Some(ref mut recorder) = *recorder.borrow_mut() {
+            let Recorder { path, changed } = recorder;
+
+            changed.segments.extend_from_slice(path);
+            changed.segments.push(name);
+            changed.ends.push(changed.segments.len());
+        }
+    });
+}
+
+/// Append child component `name` to current component path until the guard is dropped
+pub fn enter_component(name: &'static str) -> ComponentPathGuard {
+    RECORDER.with(|recorder| {
+        if let Some(ref mut recorder) = *recorder.borrow_mut() {
+            recorder.path.push(name);
+        }
+    });
+
+    ComponentPathGuard {}
+}
+
+#[derive(Debug)]
+pub struct ComponentPathGuard {}
+
+impl Drop for ComponentPathGuard {
+    fn drop(&mut self) {
+        RECORDER.with(|recorder| {
+            if let Some(ref mut recorder) = *recorder.borrow_mut() {
+                recorder.path.pop();
+            }
+        });
+    }
+}
+
+/// Report of single component update
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpdateReport {
+    signals: usize,
+    changed: ChangedPaths,
+}
+
+impl UpdateReport {
+    /// Number of signals coalesced into the update
+    pub fn signals(&self) -> usize {
+        self.signals
+    }
+
+    /// Paths of changed states in update order.
+    ///
+    /// Path is field names from the root component, like `["child", "state"]`.
+    pub fn changed(&self) -> Changed<'_> {
+        Changed {
+            paths: &self.changed,
+            start: 0,
+            index: 0,
+        }
+    }
+
+    /// Returns true if no state is changed
+    pub fn is_empty(&self) -> bool {
+        self.changed.ends.is_empty()
+    }
+
+    /// Returns true if state of `path` or any state of child component of `path` is changed
+    pub fn is_changed(&self, path: &[&str]) -> bool {
+        self.changed().any(|changed| changed.starts_with(path))
+    }
+}
+
+/// Iterator over paths of changed states
+#[derive(Debug, Clone)]
+pub struct Changed<'a> {
+    paths: &'a ChangedPaths,
+    start: usize,
+    index: usize,
+}
+
+impl<'a> Iterator for Changed<'a> {
+    type Item = &'a [&'static str];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let end = *self.paths.ends.get(self.index)?;
+        let path = &self.paths.segments[self.start..end];
+
+        self.start = end;
+        self.index += 1;
+
+        Some(path)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.paths.ends.len() - self.index;
+
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Changed<'_> {}
@@ -0,0 +1,199 @@
+//! Asynchronously loaded resource state
+
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    task::Poll,
+};
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+type Fetch<T, E> = Pin<Box<dyn Future<Output = Result<T, E>>>>;
+
+type Loader<K, T, E> = Box<dyn FnMut(&K) -> Fetch<T, E>>;
+
+/// Loading state of resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource<T, E> {
+    /// Fetch is in progress
+    Loading,
+
+    /// Fetch is completed
+    Ready(T),
+
+    /// Fetch is failed
+    Failed(E),
+}
+
+impl<T, E> Resource<T, E> {
+    /// Returns true if resource is loading
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Resource::Loading)
+    }
+
+    /// Returns loaded value
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            Resource::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns fetch error
+    pub fn failed(&self) -> Option<&E> {
+        match self {
+            Resource::Failed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// State which loads resource of key using loader.
+///
+/// Fetch starts when the cell is created or the key is changed.
+/// In-flight fetch is cancelled if the key is changed again before completion.
+/// Every transition of [`Resource`] is reported.
+pub struct ResourceCell<K, T, E> {
+    key: K,
+    loader: Loader<K, T, E>,
+    fetch: Option<Fetch<T, E>>,
+
+    changed: bool,
+    resource: Resource<T, E>,
+    tracked: Tracked,
+}
+
+impl<K, T, E> ResourceCell<K, T, E> {
+    /// Create new [`ResourceCell`] and start fetching `key`
+    pub fn new<F>(key: K, mut loader: impl FnMut(&K) -> F + 'static) -> Self
+    where
+        F: Future<Output = Result<T, E>> + 'static,
+    {
+        let mut loader: Loader<K, T, E> = Box::new(move |key| Box::pin(loader(key)));
+
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            fetch: Some(loader(&key)),
+            key,
+            loader,
+
+            changed: true,
+            resource: Resource::Loading,
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Key of current resource
+    pub fn key(this: &Self) -> &K {
+        &this.key
+    }
+
+    /// Returns true if fetch is in progress
+    pub fn is_fetching(this: &Self) -> bool {
+        this.fetch.is_some()
+    }
+
+    /// Set new key and start fetching it.
+    /// In-flight fetch is cancelled.
+    pub fn set_key(this: &mut Self, key: K) {
+        this.key = key;
+
+        ResourceCell::refresh(this);
+    }
+
+    /// Fetch current key again.
+    /// In-flight fetch is cancelled.
+    pub fn refresh(this: &mut Self) {
+        this.fetch = Some((this.loader)(&this.key));
+
+        if !this.resource.is_loading() {
+            this.resource = Resource::Loading;
+            this.changed = true;
+            this.tracked.invalidate();
+        }
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Fetch current key again only if previous fetch is failed
+    pub fn retry(this: &mut Self) {
+        if let Resource::Failed(_) = this.resource {
+            ResourceCell::refresh(this);
+        }
+    }
+}
+
+impl<K: PartialEq, T, E> ResourceCell<K, T, E> {
+    /// Set new key and start fetching it only if the key is changed
+    pub fn update_key(this: &mut Self, key: K) {
+        if this.key != key {
+            ResourceCell::set_key(this, key);
+        }
+    }
+}
+
+impl<K, T, E> Deref for ResourceCell<K, T, E> {
+    type Target = Resource<T, E>;
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.resource
+    }
+}
+
+impl<K, T, E> Unpin for ResourceCell<K, T, E> {}
+
+impl<K, T, E> State for ResourceCell<K, T, E> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        if let Some(ref mut fetch) = this.fetch {
+            if let Poll::Ready(result) =
+                with_current_context(|cx| fetch.as_mut().poll(&mut cx.task_context()))
+            {
+                this.fetch = None;
+
+                this.resource = match result {
+                    Ok(value) => Resource::Ready(value),
+                    Err(err) => Resource::Failed(err),
+                };
+                this.changed = true;
+                this.tracked.invalidate();
+            }
+        }
+
+        if this.changed {
+            this.changed = false;
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<K: Debug, T: Debug, E: Debug> Debug for ResourceCell<K, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceCell")
+            .field("key", &self.key)
+            .field("fetching", &self.fetch.is_some())
+            .field("changed", &self.changed)
+            .field("resource", &self.resource)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, T, E> Drop for ResourceCell<K, T, E> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
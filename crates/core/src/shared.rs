@@ -0,0 +1,375 @@
+//! State shared between multiple components
+
+use std::{
+    cell::{Cell, Ref, RefCell},
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::Waker,
+};
+
+use crate::{
+    context::{try_with_current_context, wake_batched, with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+#[derive(Debug)]
+struct Shared<T> {
+    value: RefCell<T>,
+    subscriptions: RefCell<Vec<Weak<Subscription>>>,
+    tracked: Tracked,
+}
+
+impl<T> Shared<T> {
+    fn subscribe(&self) -> Rc<Subscription> {
+        let subscription = Rc::new(Subscription::new());
+
+        self.subscriptions
+            .borrow_mut()
+            .push(Rc::downgrade(&subscription));
+
+        subscription
+    }
+
+    fn notify(&self) {
+        self.tracked.invalidate();
+
+        self.subscriptions
+            .borrow_mut()
+            .retain(|subscription| match subscription.upgrade() {
+                Some(subscription) => {
+                    subscription.notify();
+                    true
+                }
+
+                None => false,
+            });
+    }
+}
+
+#[derive(Debug)]
+struct Subscription {
+    changed: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl Subscription {
+    fn new() -> Self {
+        Self {
+            changed: Cell::new(true),
+            waker: RefCell::new(try_with_current_context(|cx| {
+                cx.signal();
+                cx.task_context().waker().clone()
+            })),
+        }
+    }
+
+    fn notify(&self) {
+        self.changed.set(true);
+        if let Some(ref waker) = *self.waker.borrow() {
+            wake_batched(waker);
+        }
+    }
+}
+
+/// State shared between multiple components in single thread.
+///
+/// Each handle is independent subscription with its own change flag.
+/// Cloning handle subscribes to the value using context where it is cloned.
+/// Mutating value through any handle signals every subscribing context.
+#[derive(Debug)]
+pub struct SharedState<T> {
+    shared: Rc<Shared<T>>,
+    subscription: Rc<Subscription>,
+}
+
+impl<T> SharedState<T> {
+    /// Create new [`SharedState`]
+    pub fn new(value: T) -> Self {
+        let shared = Rc::new(Shared {
+            value: RefCell::new(value),
+            subscriptions: RefCell::new(Vec::new()),
+            tracked: Tracked::new(),
+        });
+
+        let subscription = shared.subscribe();
+
+        Self {
+            shared,
+            subscription,
+        }
+    }
+
+    /// Immutably borrow shared value.
+    ///
+    /// Panics if the value is being modified.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.shared.tracked.read();
+
+        self.shared.value.borrow()
+    }
+
+    /// Set shared value and signal every subscriber
+    pub fn set(&self, value: T) {
+        *self.shared.value.borrow_mut() = value;
+
+        self.shared.notify();
+    }
+
+    /// Mutate shared value using closure and signal every subscriber.
+    ///
+    /// Panics if the value is borrowed.
+    pub fn modify<R>(&self, func: impl FnOnce(&mut T) -> R) -> R {
+        let output = func(&mut self.shared.value.borrow_mut());
+
+        self.shared.notify();
+
+        output
+    }
+
+    /// Returns true if two handles point to same value
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Rc::ptr_eq(&this.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for SharedState<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            subscription: self.shared.subscribe(),
+        }
+    }
+}
+
+impl<T> Unpin for SharedState<T> {}
+
+impl<T> State for SharedState<T> {
+    type Output = ();
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| {
+            let waker = cx.task_context().waker().clone();
+            *this.subscription.waker.borrow_mut() = Some(waker);
+        });
+
+        if this.subscription.changed.replace(false) {
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Default> Default for SharedState<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T> From<T> for SharedState<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Drop for SharedState<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+#[cfg(feature = "sync")]
+pub use sync::SyncSharedState;
+
+#[cfg(feature = "sync")]
+mod sync {
+    use std::{
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex, RwLock, RwLockReadGuard, Weak,
+        },
+    };
+
+    use atomic_waker::AtomicWaker;
+
+    use crate::{
+        context::{try_with_current_context, wake_batched, with_current_context, StateContext},
+        memo::Tracked,
+        State,
+    };
+
+    #[derive(Debug)]
+    struct Shared<T> {
+        value: RwLock<T>,
+        subscriptions: Mutex<Vec<Weak<Subscription>>>,
+        tracked: Tracked,
+    }
+
+    impl<T> Shared<T> {
+        fn subscribe(&self) -> Arc<Subscription> {
+            let subscription = Arc::new(Subscription::new());
+
+            self.subscriptions
+                .lock()
+                .unwrap()
+                .push(Arc::downgrade(&subscription));
+
+            subscription
+        }
+
+        fn notify(&self) {
+            self.tracked.invalidate();
+
+            self.subscriptions
+                .lock()
+                .unwrap()
+                .retain(|subscription| match subscription.upgrade() {
+                    Some(subscription) => {
+                        subscription.notify();
+                        true
+                    }
+
+                    None => false,
+                });
+        }
+    }
+
+    #[derive(Debug)]
+    struct Subscription {
+        changed: AtomicBool,
+        waker: AtomicWaker,
+    }
+
+    impl Subscription {
+        fn new() -> Self {
+            let waker = AtomicWaker::new();
+
+            try_with_current_context(|cx| {
+                cx.signal();
+                waker.register(cx.task_context().waker());
+            });
+
+            Self {
+                changed: AtomicBool::new(true),
+                waker,
+            }
+        }
+
+        fn notify(&self) {
+            self.changed.store(true, Ordering::Release);
+
+            // Registered again on next update
+            if let Some(waker) = self.waker.take() {
+                wake_batched(&waker);
+            }
+        }
+    }
+
+    /// State shared between multiple components across threads.
+    ///
+    /// Thread safe version of [`super::SharedState`].
+    ///
+    /// In [`crate::context::batch`], only signals are postponed.
+    /// Value is written immediately, so components updated on other threads can observe it before the batch ends.
+    /// Use [`crate::remote::RemoteState`] if writes need to be applied together.
+    #[derive(Debug)]
+    pub struct SyncSharedState<T> {
+        shared: Arc<Shared<T>>,
+        subscription: Arc<Subscription>,
+    }
+
+    impl<T> SyncSharedState<T> {
+        /// Create new [`SyncSharedState`]
+        pub fn new(value: T) -> Self {
+            let shared = Arc::new(Shared {
+                value: RwLock::new(value),
+                subscriptions: Mutex::new(Vec::new()),
+                tracked: Tracked::new(),
+            });
+
+            let subscription = shared.subscribe();
+
+            Self {
+                shared,
+                subscription,
+            }
+        }
+
+        /// Lock shared value for reading
+        pub fn read(&self) -> RwLockReadGuard<'_, T> {
+            self.shared.tracked.read();
+
+            self.shared.value.read().unwrap()
+        }
+
+        /// Set shared value and signal every subscriber
+        pub fn set(&self, value: T) {
+            *self.shared.value.write().unwrap() = value;
+
+            self.shared.notify();
+        }
+
+        /// Mutate shared value using closure and signal every subscriber
+        pub fn modify<R>(&self, func: impl FnOnce(&mut T) -> R) -> R {
+            let output = func(&mut self.shared.value.write().unwrap());
+
+            self.shared.notify();
+
+            output
+        }
+
+        /// Returns true if two handles point to same value
+        pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+            Arc::ptr_eq(&this.shared, &other.shared)
+        }
+    }
+
+    impl<T> Clone for SyncSharedState<T> {
+        fn clone(&self) -> Self {
+            Self {
+                shared: self.shared.clone(),
+                subscription: self.shared.subscribe(),
+            }
+        }
+    }
+
+    impl<T> Unpin for SyncSharedState<T> {}
+
+    impl<T> State for SyncSharedState<T> {
+        type Output = ();
+
+        fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+            let this = this.get_mut();
+
+            with_current_context(|cx| this.subscription.waker.register(cx.task_context().waker()));
+
+            if this.subscription.changed.swap(false, Ordering::AcqRel) {
+                Some(())
+            } else {
+                None
+            }
+        }
+    }
+
+    impl<T: Default> Default for SyncSharedState<T> {
+        fn default() -> Self {
+            Self::new(Default::default())
+        }
+    }
+
+    impl<T> From<T> for SyncSharedState<T> {
+        fn from(value: T) -> Self {
+            Self::new(value)
+        }
+    }
+
+    impl<T> Drop for SyncSharedState<T> {
+        fn drop(&mut self) {
+            try_with_current_context(StateContext::signal);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Futures owned by component
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug},
+    future::Future,
+    pin::Pin,
+    task::Poll,
+};
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    State,
+};
+
+type Task<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// State which owns spawned futures and polls them with waker of the component.
+///
+/// Yields one result per update. Remaining tasks are cancelled when the cell is dropped.
+pub struct TaskCell<T> {
+    tasks: Vec<Task<T>>,
+    completed: VecDeque<T>,
+}
+
+impl<T> TaskCell<T> {
+    /// Create new [`TaskCell`]
+    pub fn new() -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            tasks: Vec::new(),
+            completed: VecDeque::new(),
+        }
+    }
+
+    /// Spawn future which lives until it completes or this cell is dropped
+    pub fn spawn(&mut self, fut: impl Future<Output = T> + 'static) {
+        self.tasks.push(Box::pin(fut));
+
+        try_with_current_context(StateContext::signal);
+    }
+
+    /// Number of running tasks
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns true if there are no running tasks
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Cancel every running task.
+    /// Results which are not yielded yet are discarded.
+    pub fn cancel_all(&mut self) {
+        self.tasks.clear();
+        self.completed.clear();
+    }
+}
+
+impl<T> Default for TaskCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Unpin for TaskCell<T> {}
+
+impl<T> State for TaskCell<T> {
+    type Output = T;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| {
+            let completed = &mut this.completed;
+
+            this.tasks.retain_mut(|task| {
+                match task.as_mut().poll(&mut cx.task_context()) {
+                    Poll::Ready(output) => {
+                        completed.push_back(output);
+                        false
+                    }
+
+                    Poll::Pending => true,
+                }
+            });
+
+            let output = this.completed.pop_front();
+
+            if !this.completed.is_empty() {
+                cx.signal();
+            }
+
+            output
+        })
+    }
+}
+
+impl<T> Debug for TaskCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskCell")
+            .field("tasks", &self.tasks.len())
+            .field("completed", &self.completed.len())
+            .finish()
+    }
+}
+
+impl<T> Drop for TaskCell<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,269 @@
+//! Timer abstraction used by time based states
+//!
+//! Clock is read from [`Instant`], which is unavailable on wasm32-unknown-unknown.
+//! Time based states are not supported there.
+
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    fmt::Debug,
+    sync::{Condvar, Mutex, OnceLock},
+    task::Waker,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Timer driver which wakes states at deadline.
+///
+/// Driver is provided by executor running [`crate::context::ComponentStream`]
+/// and available through [`crate::context::StateContext::timer`].
+pub trait Timer: Debug + Send + Sync {
+    /// Current time of this timer
+    fn now(&self) -> Instant;
+
+    /// Wake `waker` when `deadline` is reached.
+    /// Waker must be woken immediately if the deadline is already passed.
+    fn register(&self, deadline: Instant, waker: Waker);
+}
+
+/// Timer entry ordered by deadline
+#[derive(Debug)]
+struct TimerEntry {
+    deadline: Instant,
+    waker: Waker,
+}
+
+impl PartialEq for TimerEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for TimerEntry {}
+
+impl PartialOrd for TimerEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TimerEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so earliest deadline is on top of max heap
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+/// Queue of timer entries which wakes expired entries
+#[derive(Debug, Default)]
+pub struct TimerQueue {
+    entries: BinaryHeap<TimerEntry>,
+}
+
+impl TimerQueue {
+    /// Create new [`TimerQueue`]
+    pub const fn new() -> Self {
+        Self {
+            entries: BinaryHeap::new(),
+        }
+    }
+
+    /// Push new entry
+    pub fn push(&mut self, deadline: Instant, waker: Waker) {
+        self.entries.push(TimerEntry { deadline, waker });
+    }
+
+    /// Earliest deadline
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.entries.peek().map(|entry| entry.deadline)
+    }
+
+    /// Wake every entries which deadline is passed
+    pub fn wake_expired(&mut self, now: Instant) {
+        while let Some(entry) = self.entries.peek() {
+            if entry.deadline > now {
+                break;
+            }
+
+            self.entries.pop().unwrap().waker.wake();
+        }
+    }
+}
+
+/// Default [`Timer`] waking entries on a background thread.
+///
+/// The thread is spawned lazily and shared by every [`ThreadTimer`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThreadTimer;
+
+#[derive(Debug)]
+struct ThreadTimerInner {
+    queue: Mutex<TimerQueue>,
+    condvar: Condvar,
+}
+
+impl ThreadTimer {
+    fn inner() -> &'static ThreadTimerInner {
+        static INNER: OnceLock<ThreadTimerInner> = OnceLock::new();
+
+        INNER.get_or_init(|| {
+            thread::Builder::new()
+                .name("async-component-timer".into())
+                .spawn(|| {
+                    let inner = ThreadTimer::inner();
+
+                    let mut queue = inner.queue.lock().unwrap();
+                    loop {
+                        queue.wake_expired(Instant::now());
+
+                        queue = match queue.next_deadline() {
+                            Some(deadline) => {
+                                let timeout = deadline.saturating_duration_since(Instant::now());
+                                inner.condvar.wait_timeout(queue, timeout).unwrap().0
+                            }
+
+                            None => inner.condvar.wait(queue).unwrap(),
+                        };
+                    }
+                })
+                .expect("Cannot spawn timer thread");
+
+            ThreadTimerInner {
+                queue: Mutex::new(TimerQueue::new()),
+                condvar: Condvar::new(),
+            }
+        })
+    }
+}
+
+impl Timer for ThreadTimer {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn register(&self, deadline: Instant, waker: Waker) {
+        if deadline <= Instant::now() {
+            waker.wake();
+            return;
+        }
+
+        let inner = Self::inner();
+
+        let mut queue = inner.queue.lock().unwrap();
+        let earliest = queue.next_deadline().is_none_or(|next| deadline < next);
+        queue.push(deadline, waker);
+
+        if earliest {
+            inner.condvar.notify_one();
+        }
+    }
+}
+
+/// [`Timer`] with manually advanced clock.
+/// Useful for testing time based states.
+#[derive(Debug)]
+pub struct ManualTimer {
+    now: Mutex<Instant>,
+    queue: Mutex<TimerQueue>,
+}
+
+impl ManualTimer {
+    /// Create new [`ManualTimer`] starting from current time
+    pub fn new() -> Self {
+        Self {
+            now: Mutex::new(Instant::now()),
+            queue: Mutex::new(TimerQueue::new()),
+        }
+    }
+
+    /// Advance clock and wake expired entries
+    pub fn advance(&self, duration: Duration) {
+        let now = {
+            let mut now = self.now.lock().unwrap();
+            *now += duration;
+
+            *now
+        };
+
+        self.queue.lock().unwrap().wake_expired(now);
+    }
+}
+
+impl Default for ManualTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timer for ManualTimer {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn register(&self, deadline: Instant, waker: Waker) {
+        if deadline <= self.now() {
+            waker.wake();
+            return;
+        }
+
+        self.queue.lock().unwrap().push(deadline, waker);
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub use self::tokio::TokioTimer;
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::{task::Waker, time::Instant};
+
+    use tokio::runtime::Handle;
+
+    use super::Timer;
+
+    /// [`Timer`] using tokio time driver.
+    ///
+    /// Clock follows [`tokio::time::Instant`] so paused time in tokio tests is respected.
+    #[derive(Debug, Clone)]
+    pub struct TokioTimer {
+        handle: Handle,
+    }
+
+    impl TokioTimer {
+        /// Create new [`TokioTimer`] using current tokio runtime.
+        ///
+        /// Panics if called outside of tokio runtime.
+        pub fn new() -> Self {
+            Self::from_handle(Handle::current())
+        }
+
+        /// Create new [`TokioTimer`] using given runtime [`Handle`]
+        pub const fn from_handle(handle: Handle) -> Self {
+            Self { handle }
+        }
+    }
+
+    impl Default for TokioTimer {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Timer for TokioTimer {
+        fn now(&self) -> Instant {
+            tokio::time::Instant::now().into_std()
+        }
+
+        fn register(&self, deadline: Instant, waker: Waker) {
+            if deadline <= self.now() {
+                waker.wake();
+                return;
+            }
+
+            self.handle.spawn(async move {
+                tokio::time::sleep_until(deadline.into()).await;
+                waker.wake();
+            });
+        }
+    }
+}
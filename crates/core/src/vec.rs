@@ -0,0 +1,252 @@
+//! List state reporting structural changes
+
+use std::{ops::Deref, pin::Pin};
+
+use crate::{
+    context::{try_with_current_context, StateContext},
+    memo::Tracked,
+    State,
+};
+
+/// Structural change of [`StateVec`].
+///
+/// Indices are relative to the list at the moment the change was made,
+/// so changes must be applied in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VecChange {
+    /// Item is inserted at index
+    Inserted(usize),
+
+    /// Item at index is removed
+    Removed(usize),
+
+    /// Item is moved from index to index
+    Moved { from: usize, to: usize },
+
+    /// Item at index is mutated
+    Updated(usize),
+
+    /// Every item is removed
+    Cleared,
+}
+
+/// List state which yields every structural change made since last update.
+///
+/// Initial items are reported as [`VecChange::Inserted`].
+#[derive(Debug)]
+pub struct StateVec<T> {
+    inner: Vec<T>,
+    changes: Vec<VecChange>,
+    tracked: Tracked,
+}
+
+impl<T> StateVec<T> {
+    /// Create new [`StateVec`]
+    pub fn new(inner: Vec<T>) -> Self {
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            changes: (0..inner.len()).map(VecChange::Inserted).collect(),
+            inner,
+            tracked: Tracked::new(),
+        }
+    }
+
+    /// Append item
+    pub fn push(&mut self, value: T) {
+        self.inner.push(value);
+
+        self.changed(VecChange::Inserted(self.inner.len() - 1));
+    }
+
+    /// Remove last item
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.inner.pop()?;
+
+        self.changed(VecChange::Removed(self.inner.len()));
+
+        Some(value)
+    }
+
+    /// Insert item at index.
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.inner.insert(index, value);
+
+        self.changed(VecChange::Inserted(index));
+    }
+
+    /// Remove item at index.
+    ///
+    /// Panics if index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let value = self.inner.remove(index);
+
+        self.changed(VecChange::Removed(index));
+
+        value
+    }
+
+    /// Move item at `from` to `to`, shifting items between.
+    ///
+    /// Panics if any index is out of bounds.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from == to {
+            assert!(from < self.inner.len(), "index out of bounds");
+            return;
+        }
+
+        let value = self.inner.remove(from);
+        self.inner.insert(to, value);
+
+        self.changed(VecChange::Moved { from, to });
+    }
+
+    /// Replace item at index and returns previous one.
+    ///
+    /// Panics if index is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) -> T {
+        let old = std::mem::replace(&mut self.inner[index], value);
+
+        self.changed(VecChange::Updated(index));
+
+        old
+    }
+
+    /// Mutably borrow item at index
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.inner.len() {
+            return None;
+        }
+
+        self.changed(VecChange::Updated(index));
+
+        self.inner.get_mut(index)
+    }
+
+    /// Remove every items
+    pub fn clear(&mut self) {
+        self.inner.clear();
+
+        self.changed(VecChange::Cleared);
+    }
+
+    /// Remove items after `len`.
+    ///
+    /// Items are reported removed from the last one.
+    pub fn truncate(&mut self, len: usize) {
+        let previous_len = self.inner.len();
+        if previous_len <= len {
+            return;
+        }
+
+        self.inner.truncate(len);
+
+        self.changes
+            .extend((len..previous_len).rev().map(VecChange::Removed));
+        self.notify();
+    }
+
+    /// Retain only items matching predicate
+    pub fn retain(&mut self, mut func: impl FnMut(&T) -> bool) {
+        let changes = &mut self.changes;
+        let mut index = 0;
+        let mut removed = 0;
+
+        self.inner.retain(|value| {
+            let retained = func(value);
+            if !retained {
+                // Items before are already removed
+                changes.push(VecChange::Removed(index - removed));
+                removed += 1;
+            }
+
+            index += 1;
+            retained
+        });
+
+        if removed > 0 {
+            self.notify();
+        }
+    }
+
+    /// Take inner [`Vec`]
+    pub fn into_inner(mut self) -> Vec<T> {
+        std::mem::take(&mut self.inner)
+    }
+
+    fn changed(&mut self, change: VecChange) {
+        self.changes.push(change);
+        self.notify();
+    }
+
+    fn notify(&mut self) {
+        self.tracked.invalidate();
+
+        try_with_current_context(StateContext::signal);
+    }
+}
+
+impl<T> Deref for StateVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.tracked.read();
+
+        &self.inner
+    }
+}
+
+impl<T> Extend<T> for StateVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let start = self.inner.len();
+        self.inner.extend(iter);
+
+        if self.inner.len() > start {
+            self.changes
+                .extend((start..self.inner.len()).map(VecChange::Inserted));
+            self.notify();
+        }
+    }
+}
+
+impl<T> Unpin for StateVec<T> {}
+
+impl<T> State for StateVec<T> {
+    type Output = Vec<VecChange>;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        if this.changes.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut this.changes))
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for StateVec<T> {
+    fn from(inner: Vec<T>) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T> FromIterator<T> for StateVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<T> Default for StateVec<T> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<T> Drop for StateVec<T> {
+    fn drop(&mut self) {
+        try_with_current_context(StateContext::signal);
+    }
+}
@@ -0,0 +1,309 @@
+//! State which watches files on disk
+
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, Thread},
+    time::{Duration, SystemTime},
+};
+
+use atomic_waker::AtomicWaker;
+
+use crate::{
+    context::{try_with_current_context, with_current_context, StateContext},
+    State,
+};
+
+/// Default interval of polling backend
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Kind of file change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileChange {
+    /// File is created
+    Created,
+
+    /// File is modified or replaced
+    Modified,
+
+    /// File is removed
+    Removed,
+}
+
+/// Change of watched file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileEvent {
+    /// Watched path
+    pub path: PathBuf,
+
+    /// Kind of change
+    pub change: FileChange,
+}
+
+#[derive(Debug)]
+struct Shared {
+    closed: AtomicBool,
+    events: Mutex<VecDeque<FileEvent>>,
+    waker: AtomicWaker,
+}
+
+impl Shared {
+    fn new() -> Self {
+        Self {
+            closed: AtomicBool::new(false),
+            events: Mutex::new(VecDeque::new()),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    fn push(&self, path: PathBuf, change: FileChange) {
+        self.events
+            .lock()
+            .unwrap()
+            .push_back(FileEvent { path, change });
+
+        self.waker.wake();
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Snapshot {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl Snapshot {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+
+    fn change(previous: &Option<Self>, current: &Option<Self>) -> Option<FileChange> {
+        match (previous, current) {
+            (None, Some(_)) => Some(FileChange::Created),
+            (Some(_), None) => Some(FileChange::Removed),
+            (Some(previous), Some(current)) if previous != current => Some(FileChange::Modified),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Backend {
+    Poll(Thread),
+
+    #[cfg(all(feature = "inotify", target_os = "linux"))]
+    Inotify(inotify::Watches, Vec<inotify::WatchDescriptor>),
+}
+
+/// State which watches files and yields [`FileEvent`] for every change.
+///
+/// Yields one event per update. Watcher thread stops when the cell is dropped.
+#[derive(Debug)]
+pub struct FileWatchCell {
+    paths: Vec<PathBuf>,
+    shared: Arc<Shared>,
+    backend: Backend,
+}
+
+impl FileWatchCell {
+    /// Create new [`FileWatchCell`] polling `paths` every [`DEFAULT_POLL_INTERVAL`]
+    pub fn new(paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        Self::with_interval(paths, DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Create new [`FileWatchCell`] polling `paths` every `interval`.
+    ///
+    /// Changes are detected by comparing modification time and length of files.
+    pub fn with_interval(
+        paths: impl IntoIterator<Item = impl Into<PathBuf>>,
+        interval: Duration,
+    ) -> Self {
+        let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+        let shared = Arc::new(Shared::new());
+
+        let thread = {
+            let shared = shared.clone();
+            let mut snapshots: Vec<_> = paths
+                .iter()
+                .map(|path| (path.clone(), Snapshot::read(path)))
+                .collect();
+
+            thread::Builder::new()
+                .name("async-component-watch".into())
+                .spawn(move || loop {
+                    thread::park_timeout(interval);
+
+                    if shared.closed.load(Ordering::Acquire) {
+                        break;
+                    }
+
+                    for (path, previous) in &mut snapshots {
+                        let current = Snapshot::read(path);
+
+                        if let Some(change) = Snapshot::change(previous, &current) {
+                            shared.push(path.clone(), change);
+                        }
+
+                        *previous = current;
+                    }
+                })
+                .expect("Cannot spawn file watch thread")
+                .thread()
+                .clone()
+        };
+
+        try_with_current_context(StateContext::signal);
+
+        Self {
+            paths,
+            shared,
+            backend: Backend::Poll(thread),
+        }
+    }
+
+    /// Create new [`FileWatchCell`] watching `paths` using inotify.
+    ///
+    /// Parent directories are watched so files replaced by rename are tracked.
+    #[cfg(all(feature = "inotify", target_os = "linux"))]
+    pub fn inotify(paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> std::io::Result<Self> {
+        use std::io;
+
+        use inotify::{EventMask, Inotify, WatchMask};
+
+        let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+        let shared = Arc::new(Shared::new());
+
+        let mut inotify = Inotify::init()?;
+        let mut watches = inotify.watches();
+
+        let mut targets = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let name = path.file_name().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name")
+            })?;
+
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+
+            let wd = watches.add(
+                dir,
+                WatchMask::CREATE
+                    | WatchMask::CLOSE_WRITE
+                    | WatchMask::MOVED_TO
+                    | WatchMask::MOVED_FROM
+                    | WatchMask::DELETE,
+            )?;
+
+            targets.push((wd, name.to_owned(), path.clone()));
+        }
+
+        let descriptors = targets.iter().map(|(wd, _, _)| wd.clone()).collect();
+
+        {
+            let shared = shared.clone();
+
+            thread::Builder::new()
+                .name("async-component-watch".into())
+                .spawn(move || {
+                    let mut buffer = [0; 4096];
+
+                    while let Ok(events) = inotify.read_events_blocking(&mut buffer) {
+                        if shared.closed.load(Ordering::Acquire) {
+                            break;
+                        }
+
+                        for event in events {
+                            let change = if event.mask.contains(EventMask::CREATE) {
+                                FileChange::Created
+                            } else if event
+                                .mask
+                                .intersects(EventMask::CLOSE_WRITE | EventMask::MOVED_TO)
+                            {
+                                FileChange::Modified
+                            } else if event
+                                .mask
+                                .intersects(EventMask::DELETE | EventMask::MOVED_FROM)
+                            {
+                                FileChange::Removed
+                            } else {
+                                continue;
+                            };
+
+                            for (wd, name, path) in &targets {
+                                if *wd == event.wd && event.name == Some(name.as_os_str()) {
+                                    shared.push(path.clone(), change);
+                                }
+                            }
+                        }
+                    }
+                })?;
+        }
+
+        try_with_current_context(StateContext::signal);
+
+        Ok(Self {
+            paths,
+            shared,
+            backend: Backend::Inotify(watches, descriptors),
+        })
+    }
+
+    /// Watched paths
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+}
+
+impl State for FileWatchCell {
+    type Output = FileEvent;
+
+    fn update(this: Pin<&mut Self>) -> Option<Self::Output> {
+        let this = this.get_mut();
+
+        with_current_context(|cx| {
+            this.shared.waker.register(cx.task_context().waker());
+
+            let mut events = this.shared.events.lock().unwrap();
+
+            let event = events.pop_front();
+            if !events.is_empty() {
+                cx.signal();
+            }
+
+            event
+        })
+    }
+}
+
+impl Drop for FileWatchCell {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+
+        match self.backend {
+            Backend::Poll(ref thread) => thread.unpark(),
+
+            // Removing watches wakes blocking read with ignored events
+            #[cfg(all(feature = "inotify", target_os = "linux"))]
+            Backend::Inotify(ref mut watches, ref mut descriptors) => {
+                for wd in descriptors.drain(..) {
+                    watches.remove(wd).ok();
+                }
+            }
+        }
+
+        try_with_current_context(StateContext::signal);
+    }
+}
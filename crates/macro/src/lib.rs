@@ -1,8 +1,11 @@
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote, quote_spanned, IdentFragment};
-use syn::{parse_macro_input, Attribute, Data, DeriveInput, ExprPath, Fields, Index};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, ExprPath,
+    Field, Fields, GenericParam, Generics, Ident, Index, Lifetime, LifetimeDef, Member, Type,
+};
 
-#[proc_macro_derive(AsyncComponent, attributes(component, state))]
+#[proc_macro_derive(AsyncComponent, attributes(component, state, memo, event, pin))]
 pub fn component_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -24,27 +27,141 @@ fn extract_path_attribute(attr: &Attribute) -> Option<ExprPath> {
     attr.parse_args::<ExprPath>().ok()
 }
 
+fn field_members(fields: &Fields) -> impl Iterator<Item = (Member, &Field)> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match field.ident {
+            Some(ref ident) => (Member::Named(ident.clone()), field),
+            None => (Member::Unnamed(Index::from(index)), field),
+        })
+}
+
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+fn is_pinned(field: &Field) -> bool {
+    extract_attribute("pin", &field.attrs).is_some()
+}
+
+/// Pinned mutable reference of field.
+/// Fields without `#[pin]` are not structurally pinned and must be [`Unpin`].
+fn field_pin_mut(member: &Member, pinned: bool) -> TokenStream {
+    if pinned {
+        quote! {
+            unsafe {
+                ::std::pin::Pin::map_unchecked_mut(::std::pin::Pin::as_mut(&mut self), |this| {
+                    &mut this.#member
+                })
+            }
+        }
+    } else {
+        quote! {
+            ::std::pin::Pin::new(unsafe {
+                &mut ::std::pin::Pin::get_unchecked_mut(::std::pin::Pin::as_mut(&mut self)).#member
+            })
+        }
+    }
+}
+
+/// Returns true if `ty` mentions any of type parameters
+fn mentions_type_param(ty: &Type, params: &[&Ident]) -> bool {
+    fn visit(tokens: TokenStream, params: &[&Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => params.contains(&&ident),
+            TokenTree::Group(group) => visit(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    visit(ty.to_token_stream(), params)
+}
+
+/// Generics of component impls.
+/// Generic fields updated without `#[pin]` are required to be [`Unpin`].
+fn component_generics(input: &DeriveInput, fields: &Fields) -> Generics {
+    let mut generics = input.generics.clone();
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    let unpinned_types = fields
+        .iter()
+        .filter(|field| {
+            !is_pinned(field)
+                && (extract_attribute("state", &field.attrs).is_some()
+                    || extract_attribute("component", &field.attrs).is_some())
+        })
+        .map(|field| &field.ty)
+        .filter(|ty| mentions_type_param(ty, &params))
+        .collect::<Vec<_>>();
+
+    if !unpinned_types.is_empty() {
+        let where_clause = generics.make_where_clause();
+
+        for ty in unpinned_types {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: ::std::marker::Unpin));
+        }
+    }
+
+    generics
+}
+
+/// Mutable reference of field which is never moved
+fn field_mut(member: &Member) -> TokenStream {
+    quote! {
+        unsafe { &mut ::std::pin::Pin::get_unchecked_mut(::std::pin::Pin::as_mut(&mut self)).#member }
+    }
+}
+
 fn impl_component_stream(input: &DeriveInput) -> TokenStream {
     let name = &input.ident;
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = match input.data {
+        Data::Struct(ref data) => component_generics(input, &data.fields),
+        _ => input.generics.clone(),
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (event_component_impl, projection_impl, unpin_impl) = match input.data {
+        Data::Struct(ref data) => (
+            event_component_impl(input, &generics, &data.fields),
+            projection_impl(input, &data.fields),
+            unpin_impl(input, &data.fields),
+        ),
+        _ => (quote!(), quote!(), quote!()),
+    };
 
     let update_component_body = match input.data {
         Data::Struct(ref data) => {
             let state_update_call = extract_attribute("component", &input.attrs)
-                .map(|attr| extract_path_attribute(attr))
+                .map(extract_path_attribute)
                 .map(|path| {
                     quote! {
-                        #path(self);
+                        ::async_component::__private::ComponentHook::call(
+                            #path,
+                            ::std::pin::Pin::as_mut(&mut self),
+                        );
                     }
                 });
 
             let state_poll = update_state_body(&data.fields);
             let component_poll = component_update_body(&data.fields);
+            let memo_refresh = memo_refresh_body(&data.fields);
 
             quote! {
                 #component_poll
 
+                #memo_refresh
+
                 #state_poll
 
                 #state_update_call
@@ -56,105 +173,293 @@ fn impl_component_stream(input: &DeriveInput) -> TokenStream {
 
     quote! {
         impl #impl_generics ::async_component::AsyncComponent for #name #ty_generics #where_clause {
-            fn update_component(&mut self) {
+            #[allow(unused_mut)]
+            fn update_component(mut self: ::std::pin::Pin<&mut Self>) {
                 #update_component_body
             }
         }
+
+        #event_component_impl
+
+        #projection_impl
+
+        #unpin_impl
     }
 }
 
-fn update_state_body(fields: &Fields) -> TokenStream {
-    match fields {
-        Fields::Named(fields) => {
-            let iter = fields.named.iter().filter_map(|field| {
-                let method_attr = extract_attribute("state", &field.attrs)?;
-                let method_path = extract_path_attribute(method_attr);
+fn event_component_impl(input: &DeriveInput, generics: &Generics, fields: &Fields) -> TokenStream {
+    let mut event_fields =
+        field_members(fields).filter(|(_, field)| extract_attribute("event", &field.attrs).is_some());
 
-                let name = field.ident.as_ref().unwrap();
+    let (member, field) = match event_fields.next() {
+        Some(event_field) => event_field,
+        None => return quote!(),
+    };
 
-                Some(field_state_update_body(name, method_path))
-            });
+    if let Some((_, field)) = event_fields.next() {
+        return quote_spanned! { field.ty.span() =>
+            compile_error!("Only one field can be marked with #[event]");
+        };
+    }
 
-            quote! {
-                #(#iter)*
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ty = &field.ty;
+    let queue = field_mut(&member);
+
+    quote! {
+        impl #impl_generics ::async_component::EventComponent for #name #ty_generics #where_clause {
+            type Event = <#ty as ::async_component::__private::QueueEvent>::Event;
+
+            fn take_events(mut self: ::std::pin::Pin<&mut Self>) -> ::std::vec::Vec<Self::Event> {
+                ::async_component::EventQueue::take(#queue)
             }
         }
-        Fields::Unnamed(fields) => {
-            let iter = fields
-                .unnamed
-                .iter()
-                .enumerate()
-                .filter_map(|(index, field)| {
-                    let method_attr = extract_attribute("state", &field.attrs)?;
-                    let method_path = extract_path_attribute(method_attr);
-
-                    let index = Index::from(index);
-
-                    Some(field_state_update_body(index, method_path))
-                });
+    }
+}
+
+/// Generate `project` method and forbid [`Drop`] implementation if any field is marked with `#[pin]`
+fn projection_impl(input: &DeriveInput, fields: &Fields) -> TokenStream {
+    if !fields.iter().any(is_pinned) {
+        return quote!();
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let projection = format_ident!("__{}Projection", name);
+    let must_not_impl_drop = format_ident!("__{}MustNotImplDrop", name);
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let lifetime = Lifetime::new("'__pin", Span::call_site());
+    let mut projection_generics = input.generics.clone();
+    projection_generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())),
+    );
+    let (_, projection_ty_generics, _) = projection_generics.split_for_impl();
 
+    let field_types = field_members(fields).map(|(member, field)| {
+        let vis = &field.vis;
+        let ty = &field.ty;
+
+        let ty = if is_pinned(field) {
+            quote!(::std::pin::Pin<&#lifetime mut #ty>)
+        } else {
+            quote!(&#lifetime mut #ty)
+        };
+
+        match member {
+            Member::Named(ident) => quote!(#vis #ident: #ty),
+            Member::Unnamed(_) => quote!(#vis #ty),
+        }
+    });
+
+    let field_values = field_members(fields).map(|(member, field)| {
+        let value = if is_pinned(field) {
+            quote!(::std::pin::Pin::new_unchecked(&mut this.#member))
+        } else {
+            quote!(&mut this.#member)
+        };
+
+        match member {
+            Member::Named(ident) => quote!(#ident: #value),
+            Member::Unnamed(_) => value,
+        }
+    });
+
+    let (projection_def, projection_value) = match fields {
+        Fields::Named(_) => (
             quote! {
-                #(#iter)*
+                #vis struct #projection #projection_generics #where_clause {
+                    #(#field_types,)*
+                }
+            },
+            quote!(#projection { #(#field_values,)* }),
+        ),
+        _ => (
+            quote! {
+                #vis struct #projection #projection_generics (#(#field_types,)*) #where_clause;
+            },
+            quote!(#projection ( #(#field_values,)* )),
+        ),
+    };
+
+    quote! {
+        /// Projection of pinned component created by derive
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #projection_def
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Project pinned component into pinned `#[pin]` fields and mutable references of other fields
+            #[allow(dead_code)]
+            #vis fn project<#lifetime>(
+                self: ::std::pin::Pin<&#lifetime mut Self>,
+            ) -> #projection #projection_ty_generics {
+                unsafe {
+                    let this = ::std::pin::Pin::get_unchecked_mut(self);
+
+                    #projection_value
+                }
             }
         }
-        Fields::Unit => {
-            quote!()
-        }
+
+        // Drop implementation can move `#[pin]` fields out of pinned component
+        #[allow(non_camel_case_types)]
+        trait #must_not_impl_drop {}
+        #[allow(drop_bounds)]
+        impl<T: ::std::ops::Drop> #must_not_impl_drop for T {}
+        impl #impl_generics #must_not_impl_drop for #name #ty_generics #where_clause {}
+    }
+}
+
+/// Implement [`Unpin`] only if every `#[pin]` field is [`Unpin`], like pin-project does.
+/// Component without `#[pin]` field is always [`Unpin`] since none of its fields is structurally pinned.
+///
+/// Manual [`Unpin`] implementation conflicts with this one, so it cannot be used to move pinned fields.
+fn unpin_impl(input: &DeriveInput, fields: &Fields) -> TokenStream {
+    let name = &input.ident;
+    let origin = format_ident!("__{}Origin", name);
+
+    let lifetime = Lifetime::new("'__pin", Span::call_site());
+    let mut origin_generics = input.generics.clone();
+    origin_generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())),
+    );
+    let (_, origin_ty_generics, origin_where_clause) = origin_generics.split_for_impl();
+
+    let mut impl_generics = origin_generics.clone();
+    impl_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#origin #origin_ty_generics: ::std::marker::Unpin));
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let type_params = input.generics.type_params().map(|param| &param.ident);
+    let lifetimes = input.generics.lifetimes().map(|def| &def.lifetime);
+
+    let pinned_fields = fields
+        .iter()
+        .filter(|field| is_pinned(field))
+        .enumerate()
+        .map(|(index, field)| {
+            let name = format_ident!("__field{}", index);
+            let ty = &field.ty;
+
+            quote!(#name: #ty)
+        });
+
+    quote! {
+        const _: () = {
+            // Holds types of `#[pin]` fields, so it is `Unpin` only if all of them are
+            #[allow(dead_code)]
+            struct #origin #origin_generics #origin_where_clause {
+                __generics: ::std::marker::PhantomData<(
+                    &#lifetime (),
+                    #(fn(&#type_params),)*
+                    #(&#lifetimes (),)*
+                )>,
+                #(#pinned_fields,)*
+            }
+
+            #[allow(private_bounds)]
+            impl #impl_generics ::std::marker::Unpin for #name #ty_generics #where_clause {}
+        };
+    }
+}
+
+fn update_state_body(fields: &Fields) -> TokenStream {
+    let iter = field_members(fields).filter_map(|(member, field)| {
+        let method_attr = extract_attribute("state", &field.attrs)?;
+        let method_path = extract_path_attribute(method_attr);
+
+        Some(field_state_update_body(&member, is_pinned(field), method_path))
+    });
+
+    quote! {
+        #(#iter)*
     }
 }
 
-fn field_state_update_body(name: impl IdentFragment, method_path: Option<ExprPath>) -> TokenStream {
-    let name = format_ident!("{}", name);
+fn field_state_update_body(
+    member: &Member,
+    pinned: bool,
+    method_path: Option<ExprPath>,
+) -> TokenStream {
+    let state_path = member_name(member);
+    let state = field_pin_mut(member, pinned);
 
-    let method_call = method_path.map(|path| quote! { #path(self, _recv); });
+    let method_call = method_path.map(|path| {
+        quote! {
+            ::async_component::__private::StateHook::call(
+                #path,
+                ::std::pin::Pin::as_mut(&mut self),
+                _recv,
+            );
+        }
+    });
 
-    quote_spanned! { name.span() =>
-        if let Some(_recv) = ::async_component::State::update(&mut self.#name) {
+    quote_spanned! { member.span() =>
+        if let Some(_recv) = ::async_component::State::update(#state) {
+            ::async_component::__private::record_changed(#state_path);
             #method_call
         }
     }
 }
 
 fn component_update_body(fields: &Fields) -> TokenStream {
-    match fields {
-        Fields::Named(fields) => {
-            let iter = fields.named.iter().filter_map(|field| {
-                let _ = extract_attribute("component", &field.attrs)?;
-                let name = field.ident.as_ref().unwrap();
+    let iter = field_members(fields)
+        .enumerate()
+        .filter_map(|(index, (member, field))| {
+            let _ = extract_attribute("component", &field.attrs)?;
 
-                Some(field_component_update_body(name))
-            });
+            Some(field_component_update_body(
+                index,
+                &member,
+                is_pinned(field),
+            ))
+        });
 
-            quote! {
-                #(#iter)*
-            }
-        }
-        Fields::Unnamed(fields) => {
-            let iter = fields
-                .unnamed
-                .iter()
-                .enumerate()
-                .filter_map(|(index, field)| {
-                    let _ = extract_attribute("component", &field.attrs)?;
-                    let index = Index::from(index);
-
-                    Some(field_component_update_body(index))
-                });
+    quote! {
+        #(#iter)*
+    }
+}
 
-            quote! {
-                #(#iter)*
-            }
-        }
-        Fields::Unit => {
-            quote!()
+/// Update child component in its own scope, which is skipped if it is not signaled
+fn field_component_update_body(index: usize, member: &Member, pinned: bool) -> TokenStream {
+    let component_path = member_name(member);
+    let component = field_pin_mut(member, pinned);
+
+    quote_spanned! { member.span() =>
+        if let ::std::option::Option::Some(_scope) = ::async_component::__private::enter_scope(#index) {
+            let _path = ::async_component::__private::enter_component(#component_path);
+            ::async_component::AsyncComponent::update_component(#component);
         }
     }
 }
 
-fn field_component_update_body(name: impl IdentFragment) -> TokenStream {
-    let name = format_ident!("{}", name);
+fn memo_refresh_body(fields: &Fields) -> TokenStream {
+    let iter = field_members(fields).filter_map(|(member, field)| {
+        let method_path = extract_path_attribute(extract_attribute("memo", &field.attrs)?)?;
+
+        Some(field_memo_refresh_body(&member, method_path))
+    });
 
-    quote_spanned! { name.span() =>
-        ::async_component::AsyncComponent::update_component(&mut self.#name);
+    quote! {
+        #(#iter)*
+    }
+}
+
+fn field_memo_refresh_body(member: &Member, method_path: ExprPath) -> TokenStream {
+    let memo = field_mut(member);
+
+    quote_spanned! { member.span() =>
+        if ::async_component::memo::MemoCell::is_stale(&self.#member) {
+            let (_value, _dependencies) = ::async_component::memo::track(|| #method_path(&*self));
+            ::async_component::__private::store_computed(#memo, _value, _dependencies);
+        }
     }
 }
@@ -1,12 +1,14 @@
 //! Specialized async Executor built on top of winit event loop for running [`AsyncComponent`]
 
 pub mod signal;
+pub mod timer;
 #[cfg(target_arch = "wasm32")]
 mod wasm;
 
 use std::{
-    sync::atomic::Ordering,
+    sync::{atomic::Ordering, Arc},
     task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    time::Instant,
 };
 
 use async_component_core::{context::ComponentStream, AsyncComponent};
@@ -20,7 +22,7 @@ use crate::WinitComponent;
 
 use ref_extended::ref_extended;
 
-use self::signal::WinitSignal;
+use self::{signal::WinitSignal, timer::WinitTimer};
 
 /// Reserved zero sized user event struct used for waking winit eventloop
 #[derive(Debug, Clone, Copy)]
@@ -30,22 +32,27 @@ pub struct ExecutorPollEvent;
 /// Executor implemented on top of winit eventloop using user event.
 ///
 /// See [`WinitSignal`] for more detail how it utilize winit user event.
+/// Time based states are driven by [`WinitTimer`].
+/// They are not supported on wasm32, where [`Instant::now`] is unavailable.
 #[derive(Debug)]
 pub struct WinitExecutor {
     event_loop: Option<EventLoop<ExecutorPollEvent>>,
 
     state_signal: WinitSignal,
+    timer: Arc<WinitTimer>,
 }
 
 impl WinitExecutor {
     /// Create new [`WinitExecutor`]
     pub fn new(event_loop: EventLoop<ExecutorPollEvent>) -> Self {
         let state_signal = WinitSignal::new(event_loop.create_proxy());
+        let timer = Arc::new(WinitTimer::new(event_loop.create_proxy()));
 
         Self {
             event_loop: Some(event_loop),
 
             state_signal,
+            timer,
         }
     }
 
@@ -70,16 +77,28 @@ impl WinitExecutor {
         }
     }
 
+    /// Wake expired timers and returns next deadline
+    #[cfg(not(target_arch = "wasm32"))]
+    fn wake_timers(&self) -> Option<Instant> {
+        self.timer.wake_expired(Instant::now())
+    }
+
+    /// Timers are never driven on wasm32 since [`Instant::now`] panics there
+    #[cfg(target_arch = "wasm32")]
+    fn wake_timers(&self) -> Option<Instant> {
+        None
+    }
+
     /// Initializes the winit event loop and run component.
     ///
     /// See [`EventLoop`] for more detail about winit event loop
-    pub fn run<C: AsyncComponent + WinitComponent + 'static>(
+    pub fn run<C: AsyncComponent + WinitComponent + Unpin + 'static>(
         mut self,
         func: impl FnOnce() -> C,
     ) -> ! {
         let event_loop = self.event_loop.take().unwrap();
 
-        let mut stream = ComponentStream::new(func);
+        let mut stream = ComponentStream::with_timer(self.timer.clone(), func);
 
         let executor = self;
         ref_extended!(|&executor| event_loop.run(move |event, _, control_flow| {
@@ -95,14 +114,24 @@ impl WinitExecutor {
                         return;
                     }
 
+                    let next_deadline = executor.wake_timers();
+
                     match executor.poll_stream(&mut stream) {
                         Poll::Ready(_) => {
                             control_flow.set_poll();
                         }
 
-                        Poll::Pending => {
-                            control_flow.set_wait();
-                        }
+                        Poll::Pending => match next_deadline {
+                            // winit uses its own Instant type on wasm
+                            #[cfg(not(target_arch = "wasm32"))]
+                            Some(deadline) => {
+                                control_flow.set_wait_until(deadline);
+                            }
+
+                            _ => {
+                                control_flow.set_wait();
+                            }
+                        },
                     }
                 }
 
@@ -0,0 +1,64 @@
+use std::{task::Waker, time::Instant};
+
+use async_component_core::timer::{Timer, TimerQueue};
+use parking_lot::Mutex;
+use winit::event_loop::EventLoopProxy;
+
+use super::ExecutorPollEvent;
+
+/// [`Timer`] driven by winit eventloop.
+///
+/// Eventloop waits until earliest deadline using [`winit::event_loop::ControlFlow::WaitUntil`].
+/// New earliest deadline wakes eventloop using [`ExecutorPollEvent`] user event so it can wait again with updated deadline.
+///
+/// Not supported on wasm32-unknown-unknown. [`Instant::now`] panics there,
+/// so time based states like `DebounceCell` or `IntervalCell` cannot be used.
+#[derive(Debug)]
+pub struct WinitTimer {
+    queue: Mutex<TimerQueue>,
+    proxy: Mutex<EventLoopProxy<ExecutorPollEvent>>,
+}
+
+impl WinitTimer {
+    /// Create new [`WinitTimer`] with given [`EventLoopProxy`]
+    pub const fn new(proxy: EventLoopProxy<ExecutorPollEvent>) -> Self {
+        Self {
+            queue: Mutex::new(TimerQueue::new()),
+            proxy: Mutex::new(proxy),
+        }
+    }
+
+    /// Wake expired entries and returns next deadline
+    pub fn wake_expired(&self, now: Instant) -> Option<Instant> {
+        let mut queue = self.queue.lock();
+        queue.wake_expired(now);
+
+        queue.next_deadline()
+    }
+}
+
+impl Timer for WinitTimer {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn register(&self, deadline: Instant, waker: Waker) {
+        if deadline <= Instant::now() {
+            waker.wake();
+            return;
+        }
+
+        let earliest = {
+            let mut queue = self.queue.lock();
+
+            let earliest = queue.next_deadline().is_none_or(|next| deadline < next);
+            queue.push(deadline, waker);
+
+            earliest
+        };
+
+        if earliest {
+            self.proxy.lock().send_event(ExecutorPollEvent).ok();
+        }
+    }
+}
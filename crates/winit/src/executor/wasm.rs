@@ -1,10 +1,14 @@
-use super::WinitSignal;
+use super::{WinitSignal, WinitTimer};
 
 // No thread in wasm
 unsafe impl Send for WinitSignal {}
 
 unsafe impl Sync for WinitSignal {}
 
+unsafe impl Send for WinitTimer {}
+
+unsafe impl Sync for WinitTimer {}
+
 #[cfg(test)]
 #[test]
 fn wasm_thread_test() {
@@ -15,7 +15,7 @@ pub trait WinitComponent {
 }
 
 /// Convenience method for initializing executor and running winit eventloop
-pub fn run<C: AsyncComponent + WinitComponent + 'static>(
+pub fn run<C: AsyncComponent + WinitComponent + Unpin + 'static>(
     event_loop: EventLoop<ExecutorPollEvent>,
     func: impl FnOnce() -> C,
 ) -> ! {
@@ -61,6 +61,12 @@ impl App {
     }
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AppElement for App {
     // Draw children elements
     fn draw(&self, target: &mut DrawTarget) {
@@ -75,12 +81,13 @@ impl AppElement for App {
 impl WinitComponent for App {
     fn on_event(&mut self, event: &mut Event<()>, _: &mut ControlFlow) {
         match *event {
-            // Update position state to actual cursor position
+            // Update position state to actual cursor position.
+            // Redraw only if the position is actually changed.
             Event::WindowEvent {
                 event: WindowEvent::CursorMoved { ref position, .. },
                 ..
             } => {
-                *self.cursor.position = (position.x as _, position.y as _);
+                StateCell::set(&mut self.cursor.position, (position.x as _, position.y as _));
             }
 
             // Add center_box element on left click